@@ -0,0 +1,10 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use advent_of_code_2023::solution::find;
+
+fuzz_target!(|data: &[u8]| {
+    // Malformed input must be reported as an error, never panic
+    let _ = find(2023, 2).unwrap().parse(&String::from_utf8_lossy(data));
+});
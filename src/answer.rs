@@ -0,0 +1,166 @@
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+/// Answer to a part, whatever integer type or text the solution produced
+///
+/// Integers are stored normalized, so answers of different integer types compare equal,
+/// and text that reads as an integer is stored as that integer.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Answer {
+    Int(i128),
+    /// Only for values above `i128::MAX`
+    UInt(u128),
+    Text(String),
+}
+
+macro_rules! impl_from_int {
+    ($($int:ty),*) => {
+        $(
+            impl From<$int> for Answer {
+                fn from(value: $int) -> Self {
+                    Answer::Int(value as i128)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, usize);
+
+impl From<u128> for Answer {
+    fn from(value: u128) -> Self {
+        match i128::try_from(value) {
+            Ok(value) => Answer::Int(value),
+            Err(_) => Answer::UInt(value),
+        }
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Self {
+        if let Ok(value) = text.parse::<i128>() {
+            Answer::Int(value)
+        } else if let Ok(value) = text.parse::<u128>() {
+            Answer::UInt(value)
+        } else {
+            Answer::Text(text.to_string())
+        }
+    }
+}
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Answer::from(text.as_str())
+    }
+}
+
+impl FromStr for Answer {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Answer::from(s))
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Int(value) => write!(f, "{}", value),
+            Answer::UInt(value) => write!(f, "{}", value),
+            Answer::Text(text) => write!(f, "{}", text),
+        }
+    }
+}
+
+/// Integers that fit 64 bits are numbers, anything else is a string
+impl Serialize for Answer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match *self {
+            Answer::Int(value) => match (i64::try_from(value), u64::try_from(value)) {
+                (Ok(value), _) => serializer.serialize_i64(value),
+                (_, Ok(value)) => serializer.serialize_u64(value),
+                _ => serializer.collect_str(self),
+            },
+            _ => serializer.collect_str(self),
+        }
+    }
+}
+
+struct AnswerVisitor;
+
+impl Visitor<'_> for AnswerVisitor {
+    type Value = Answer;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "an integer or a string")
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Answer, E> {
+        Ok(Answer::from(value))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Answer, E> {
+        Ok(Answer::from(value))
+    }
+
+    fn visit_i128<E: de::Error>(self, value: i128) -> Result<Answer, E> {
+        Ok(Answer::from(value))
+    }
+
+    fn visit_u128<E: de::Error>(self, value: u128) -> Result<Answer, E> {
+        Ok(Answer::from(value))
+    }
+
+    fn visit_str<E: de::Error>(self, text: &str) -> Result<Answer, E> {
+        Ok(Answer::from(text))
+    }
+}
+
+impl<'de> Deserialize<'de> for Answer {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(AnswerVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_equality() {
+        assert_eq!(Answer::from(71503u64), Answer::from(71503i32));
+        assert_eq!(Answer::from(-5i64), "-5".parse().unwrap());
+        assert_eq!(Answer::from(u128::MAX), Answer::UInt(u128::MAX));
+        assert_eq!(Answer::from("ABCDEF"), Answer::Text("ABCDEF".to_string()));
+        assert_ne!(Answer::from(1), Answer::from("01x"));
+    }
+
+    #[test]
+    fn test_serde() {
+        let answers = vec![
+            Answer::from(53386),
+            Answer::from(-2i64),
+            Answer::from(u64::MAX),
+            Answer::from(i128::MIN),
+            Answer::from(u128::MAX),
+            Answer::from("LJKGH"),
+        ];
+        let json = serde_json::to_string(&answers).unwrap();
+        assert_eq!(
+            json,
+            format!(
+                "[53386,-2,{},\"{}\",\"{}\",\"LJKGH\"]",
+                u64::MAX,
+                i128::MIN,
+                u128::MAX
+            )
+        );
+        assert_eq!(serde_json::from_str::<Vec<Answer>>(&json).unwrap(), answers);
+        assert!(serde_json::from_str::<Answer>("[1]").is_err());
+    }
+}
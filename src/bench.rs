@@ -0,0 +1,293 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+
+use crate::solution::{Entry, SolveError};
+
+/// Distribution of the wall-clock time of parsing and solving a part
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub samples: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub p90: Duration,
+    pub p99: Duration,
+    pub max: Duration,
+}
+
+/// Nearest-rank percentile of sorted samples
+fn percentile(sorted: &[Duration], percent: usize) -> Duration {
+    let rank = (percent * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+impl Stats {
+    /// None without any samples
+    pub fn from_samples(samples: &[Duration]) -> Option<Stats> {
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        Some(Stats {
+            samples: sorted.len(),
+            min: *sorted.first()?,
+            median: percentile(&sorted, 50),
+            p90: percentile(&sorted, 90),
+            p99: percentile(&sorted, 99),
+            max: *sorted.last()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    /// Untimed runs before measuring
+    pub warmup: usize,
+    pub samples: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            warmup: 3,
+            samples: 50,
+        }
+    }
+}
+
+/// Time parsing the input and solving the part, `config.samples` times
+pub fn measure(entry: &Entry, part: u32, input: &str, config: Config) -> Result<Stats, SolveError> {
+    for _ in 0..config.warmup {
+        entry.run(input, part)?;
+    }
+    let mut samples = Vec::with_capacity(config.samples);
+    for _ in 0..config.samples.max(1) {
+        let start = Instant::now();
+        entry.run(input, part)?;
+        samples.push(start.elapsed());
+    }
+    Ok(Stats::from_samples(&samples).unwrap())
+}
+
+/// FNV-1a hash of the input, stable across builds unlike `DefaultHasher`
+pub fn input_hash(input: &str) -> u64 {
+    input.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Benchmark result of a day's part, as stored in the baseline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Record {
+    pub day: u32,
+    pub part: u32,
+    /// [`input_hash`] of the input measured, timings of other inputs aren't comparable
+    pub input: u64,
+    pub stats: Stats,
+}
+
+impl Record {
+    fn to_json(self) -> Value {
+        json!({
+            "day": self.day,
+            "part": self.part,
+            "input": format!("{:016x}", self.input),
+            "samples": self.stats.samples,
+            "min_ns": self.stats.min.as_nanos() as u64,
+            "median_ns": self.stats.median.as_nanos() as u64,
+            "p90_ns": self.stats.p90.as_nanos() as u64,
+            "p99_ns": self.stats.p99.as_nanos() as u64,
+            "max_ns": self.stats.max.as_nanos() as u64,
+        })
+    }
+
+    fn from_json(value: &Value) -> Option<Record> {
+        let number = |key: &str| value.get(key).and_then(Value::as_u64);
+        let nanos = |key: &str| number(key).map(Duration::from_nanos);
+        Some(Record {
+            day: number("day")? as u32,
+            part: number("part")? as u32,
+            input: u64::from_str_radix(value.get("input")?.as_str()?, 16).ok()?,
+            stats: Stats {
+                samples: number("samples")? as usize,
+                min: nanos("min_ns")?,
+                median: nanos("median_ns")?,
+                p90: nanos("p90_ns")?,
+                p99: nanos("p99_ns")?,
+                max: nanos("max_ns")?,
+            },
+        })
+    }
+}
+
+fn invalid_data(path: &Path, reason: impl std::fmt::Display) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("invalid baseline {}: {}", path.display(), reason),
+    )
+}
+
+/// Read a baseline written by [`save_baseline`], a missing file is an empty baseline
+pub fn load_baseline(path: &Path) -> io::Result<Vec<Record>> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let value: Value = serde_json::from_str(&content).map_err(|e| invalid_data(path, e))?;
+    value
+        .as_array()
+        .ok_or_else(|| invalid_data(path, "expected an array"))?
+        .iter()
+        .map(|record| {
+            Record::from_json(record).ok_or_else(|| invalid_data(path, format!("{}", record)))
+        })
+        .collect()
+}
+
+/// Replace the baseline records of the benchmarked parts, keeping the others
+pub fn save_baseline(path: &Path, baseline: &[Record], records: &[Record]) -> io::Result<()> {
+    let mut merged: Vec<Record> = baseline
+        .iter()
+        .filter(|old| {
+            !records
+                .iter()
+                .any(|new| (new.day, new.part) == (old.day, old.part))
+        })
+        .chain(records)
+        .copied()
+        .collect();
+    merged.sort_by_key(|record| (record.day, record.part));
+
+    let values: Vec<Value> = merged.into_iter().map(Record::to_json).collect();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(
+        path,
+        format!("{}\n", serde_json::to_string_pretty(&values).unwrap()),
+    )
+}
+
+/// Median of a benchmark compared to its baseline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Comparison {
+    pub baseline: Duration,
+    pub current: Duration,
+}
+
+impl Comparison {
+    /// None unless the baseline has the part measured on the same input
+    pub fn find(baseline: &[Record], record: &Record) -> Option<Comparison> {
+        baseline
+            .iter()
+            .find(|old| (old.day, old.part, old.input) == (record.day, record.part, record.input))
+            .map(|old| Comparison {
+                baseline: old.stats.median,
+                current: record.stats.median,
+            })
+    }
+
+    /// Relative change of the median, 0.1 is 10% slower
+    pub fn change(&self) -> f64 {
+        self.current.as_secs_f64() / self.baseline.as_secs_f64().max(f64::MIN_POSITIVE) - 1.0
+    }
+
+    /// Slower than the baseline by more than `threshold`, e.g. 0.1 for 10%
+    pub fn is_regression(&self, threshold: f64) -> bool {
+        self.change() > threshold
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::env;
+
+    use super::*;
+    use crate::examples::example;
+    use crate::solution::find;
+
+    fn millis(ms: &[u64]) -> Vec<Duration> {
+        ms.iter().map(|&ms| Duration::from_millis(ms)).collect()
+    }
+
+    #[test]
+    fn test_stats() {
+        let samples = millis(&[9, 1, 8, 2, 7, 3, 6, 4, 5, 10]);
+        let stats = Stats::from_samples(&samples).unwrap();
+        assert_eq!(
+            (stats.min, stats.median, stats.p90, stats.p99, stats.max),
+            (
+                Duration::from_millis(1),
+                Duration::from_millis(5),
+                Duration::from_millis(9),
+                Duration::from_millis(10),
+                Duration::from_millis(10)
+            )
+        );
+        assert_eq!(
+            Stats::from_samples(&millis(&[3])).unwrap().median,
+            Duration::from_millis(3)
+        );
+        assert_eq!(Stats::from_samples(&[]), None);
+    }
+
+    #[test]
+    fn test_input_hash() {
+        assert_eq!(input_hash(""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(input_hash("a"), 0xaf63_dc4c_8601_ec8c);
+        assert_ne!(input_hash("12\n"), input_hash("21\n"));
+    }
+
+    #[test]
+    fn test_baseline() {
+        let entry = find(2023, 6).unwrap();
+        let config = Config {
+            warmup: 0,
+            samples: 5,
+        };
+        let stats = measure(entry, 2, &example(6, "part1"), config).unwrap();
+        assert_eq!(stats.samples, 5);
+        assert!(measure(entry, 2, "Time: 7", config).is_err());
+
+        let path = env::temp_dir()
+            .join(format!("aoc-bench-{}", std::process::id()))
+            .join("2023.json");
+        let old = Record {
+            day: 6,
+            part: 2,
+            input: input_hash(&example(6, "part1")),
+            stats,
+        };
+        let other = Record {
+            day: 1,
+            part: 1,
+            input: input_hash(&example(1, "part1")),
+            stats,
+        };
+        save_baseline(&path, &[], &[old, other]).unwrap();
+        let slower = Stats {
+            median: stats.median * 2,
+            ..stats
+        };
+        let new = Record {
+            stats: slower,
+            ..old
+        };
+        save_baseline(&path, &load_baseline(&path).unwrap(), &[new]).unwrap();
+        assert_eq!(load_baseline(&path).unwrap(), vec![other, new]);
+
+        let comparison = Comparison::find(&[old], &new).unwrap();
+        assert!(comparison.is_regression(0.5));
+        assert!(!comparison.is_regression(1.5));
+        assert_eq!(Comparison::find(&[other], &new), None);
+        let other_input = Record {
+            input: input_hash("Time: 7\nDistance: 9"),
+            ..new
+        };
+        assert_eq!(Comparison::find(&[old], &other_input), None);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
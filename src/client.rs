@@ -0,0 +1,418 @@
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use ureq::Agent;
+
+pub const BASE_URL: &str = "https://adventofcode.com";
+
+/// Environment variable with an email address or URL where the site's maintainers can reach the user
+pub const CONTACT_ENV: &str = "AOC_CONTACT";
+
+/// Identifies the tool, and whom to contact about its requests when known
+pub fn user_agent(contact: Option<&str>) -> String {
+    let tool = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+    match contact {
+        Some(contact) => format!(
+            "{} (+{}; personal Advent of Code helper, built on ureq)",
+            tool, contact
+        ),
+        None => format!("{} (personal Advent of Code helper, built on ureq)", tool),
+    }
+}
+
+/// The contact in `AOC_CONTACT`, or the repository in Cargo.toml
+pub fn contact() -> Option<String> {
+    env::var(CONTACT_ENV)
+        .ok()
+        .or_else(|| Some(env!("CARGO_PKG_REPOSITORY").to_string()))
+        .filter(|contact| !contact.is_empty())
+}
+
+/// Whether a session token looks like one from the website, the server fails on malformed ones
+fn is_well_formed(session: &str) -> bool {
+    !session.is_empty() && session.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Default minimum time between two requests to the server
+pub const DEFAULT_DELAY: Duration = Duration::from_secs(1);
+
+#[derive(Debug)]
+pub enum ClientError {
+    MissingSession,
+    NotLoggedIn,
+    Locked { year: u32, day: u32 },
+    Http { status: u16, body: String },
+    Transport(ureq::Error),
+    Io(io::Error),
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ClientError::MissingSession => write!(
+                f,
+                "no session token, set {} or write it to the session file",
+                crate::fetch::SESSION_ENV
+            ),
+            ClientError::NotLoggedIn => write!(f, "session token was not accepted, log in again"),
+            ClientError::Locked { year, day } => {
+                write!(f, "day {} of {} is not unlocked yet", day, year)
+            }
+            ClientError::Http { status, body } => {
+                write!(f, "unexpected response {}: {}", status, body.trim())
+            }
+            ClientError::Transport(e) => write!(f, "request failed: {}", e),
+            ClientError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for ClientError {}
+
+impl From<ureq::Error> for ClientError {
+    fn from(e: ureq::Error) -> Self {
+        ClientError::Transport(e)
+    }
+}
+
+impl From<io::Error> for ClientError {
+    fn from(e: io::Error) -> Self {
+        ClientError::Io(e)
+    }
+}
+
+/// Days since 1970-01-01 of a date in the proleptic Gregorian calendar
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// Puzzles unlock at midnight EST (UTC-5)
+pub fn unlock_time(year: u32, day: u32) -> SystemTime {
+    let days = days_from_civil(year as i64, 12, day as i64) as u64;
+    UNIX_EPOCH + Duration::from_secs(days * 86400 + 5 * 3600)
+}
+
+pub fn is_unlocked(year: u32, day: u32, now: SystemTime) -> bool {
+    now >= unlock_time(year, day)
+}
+
+/// Talks to the Advent of Code website with a session token, waiting between requests
+pub struct Client {
+    agent: Agent,
+    base_url: String,
+    session: String,
+    user_agent: String,
+    delay: Duration,
+    last_request: Option<Instant>,
+}
+
+impl Client {
+    pub fn new(session: &str) -> Self {
+        let agent = Agent::config_builder()
+            .http_status_as_error(false)
+            .timeout_global(Some(Duration::from_secs(30)))
+            .build()
+            .into();
+        Client {
+            agent,
+            base_url: BASE_URL.to_string(),
+            session: session.to_string(),
+            user_agent: user_agent(contact().as_deref()),
+            delay: DEFAULT_DELAY,
+            last_request: None,
+        }
+    }
+
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn with_user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = user_agent.to_string();
+        self
+    }
+
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Sleep until `delay` has passed since the previous request
+    fn wait_for_turn(&mut self) {
+        if let Some(last_request) = self.last_request {
+            let elapsed = last_request.elapsed();
+            if elapsed < self.delay {
+                thread::sleep(self.delay - elapsed);
+            }
+        }
+        self.last_request = Some(Instant::now());
+    }
+
+    fn get(&mut self, path: &str) -> Result<(u16, String), ClientError> {
+        self.wait_for_turn();
+        let mut response = self
+            .agent
+            .get(format!("{}{}", self.base_url, path))
+            .header("User-Agent", &self.user_agent)
+            .header("Cookie", format!("session={}", self.session))
+            .call()?;
+        let body = response.body_mut().read_to_string()?;
+        Ok((response.status().as_u16(), body))
+    }
+
+    fn post_form(
+        &mut self,
+        path: &str,
+        form: &[(&str, &str)],
+    ) -> Result<(u16, String), ClientError> {
+        self.wait_for_turn();
+        let mut response = self
+            .agent
+            .post(format!("{}{}", self.base_url, path))
+            .header("User-Agent", &self.user_agent)
+            .header("Cookie", format!("session={}", self.session))
+            .send_form(form.iter().copied())?;
+        let body = response.body_mut().read_to_string()?;
+        Ok((response.status().as_u16(), body))
+    }
+
+    /// Whether the server refused the request because of the session token
+    fn rejects_session(&self, status: u16, body: &str) -> bool {
+        match status {
+            400 => body.contains("log in"),
+            // A malformed session cookie makes the server fail instead
+            500 => !is_well_formed(&self.session),
+            _ => false,
+        }
+    }
+
+    /// Download the puzzle input for a day
+    pub fn input(&mut self, year: u32, day: u32) -> Result<String, ClientError> {
+        let (status, body) = self.get(&format!("/{}/day/{}/input", year, day))?;
+        match status {
+            200 => Ok(body),
+            404 => Err(ClientError::Locked { year, day }),
+            _ if self.rejects_session(status, &body) => Err(ClientError::NotLoggedIn),
+            _ => Err(ClientError::Http { status, body }),
+        }
+    }
+
+    /// Post an answer for a day's part and return the resulting page
+    pub fn answer(
+        &mut self,
+        year: u32,
+        day: u32,
+        part: u32,
+        answer: &str,
+    ) -> Result<String, ClientError> {
+        let level = part.to_string();
+        let (status, body) = self.post_form(
+            &format!("/{}/day/{}/answer", year, day),
+            &[("level", &level), ("answer", answer)],
+        )?;
+        match status {
+            200 => Ok(body),
+            404 => Err(ClientError::Locked { year, day }),
+            _ if self.rejects_session(status, &body) => Err(ClientError::NotLoggedIn),
+            _ => Err(ClientError::Http { status, body }),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod mock {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+    use std::thread::{self, JoinHandle};
+
+    /// A request as received by the mock server
+    #[derive(Debug, Clone)]
+    pub struct Request {
+        pub request_line: String,
+        pub headers: Vec<(String, String)>,
+        pub body: String,
+    }
+
+    impl Request {
+        pub fn header(&self, name: &str) -> Option<&str> {
+            self.headers
+                .iter()
+                .find(|(n, _)| n.eq_ignore_ascii_case(name))
+                .map(|(_, v)| v.as_str())
+        }
+    }
+
+    /// Local HTTP server answering each request with the next canned (status, body)
+    pub struct MockServer {
+        pub url: String,
+        requests: Arc<Mutex<Vec<Request>>>,
+        handle: Option<JoinHandle<()>>,
+    }
+
+    impl MockServer {
+        pub fn start(responses: Vec<(u16, String)>) -> MockServer {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = format!("http://{}", listener.local_addr().unwrap());
+            let requests = Arc::new(Mutex::new(Vec::new()));
+            let received = Arc::clone(&requests);
+            let handle = thread::spawn(move || {
+                for (status, body) in responses {
+                    let (mut stream, _) = listener.accept().unwrap();
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+                    let mut request_line = String::new();
+                    reader.read_line(&mut request_line).unwrap();
+                    let mut headers = Vec::new();
+                    loop {
+                        let mut line = String::new();
+                        reader.read_line(&mut line).unwrap();
+                        match line.trim_end().split_once(": ") {
+                            Some((name, value)) => {
+                                headers.push((name.to_string(), value.to_string()))
+                            }
+                            None => break,
+                        }
+                    }
+                    let length = headers
+                        .iter()
+                        .find(|(n, _): &&(String, String)| n.eq_ignore_ascii_case("content-length"))
+                        .map_or(0, |(_, v)| v.parse().unwrap());
+                    let mut request_body = vec![0; length];
+                    reader.read_exact(&mut request_body).unwrap();
+                    received.lock().unwrap().push(Request {
+                        request_line: request_line.trim_end().to_string(),
+                        headers,
+                        body: String::from_utf8(request_body).unwrap(),
+                    });
+                    write!(
+                        stream,
+                        "HTTP/1.1 {} Mock\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        status,
+                        body.len(),
+                        body
+                    )
+                    .unwrap();
+                }
+            });
+            MockServer {
+                url,
+                requests,
+                handle: Some(handle),
+            }
+        }
+
+        /// Wait until every canned response was served and return the received requests
+        pub fn finish(mut self) -> Vec<Request> {
+            self.handle.take().unwrap().join().unwrap();
+            self.requests.lock().unwrap().clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::mock::MockServer;
+    use super::*;
+
+    #[test]
+    fn test_unlock_time() {
+        assert_eq!(
+            unlock_time(2023, 1),
+            UNIX_EPOCH + Duration::from_secs(1701406800)
+        );
+        assert_eq!(
+            unlock_time(2023, 25),
+            unlock_time(2023, 1) + Duration::from_secs(24 * 86400)
+        );
+        assert!(!is_unlocked(
+            2023,
+            2,
+            unlock_time(2023, 2) - Duration::from_secs(1)
+        ));
+        assert!(is_unlocked(2023, 2, unlock_time(2023, 2)));
+    }
+
+    #[test]
+    fn test_input() {
+        let server = MockServer::start(vec![
+            (200, "1abc2\n".to_string()),
+            (
+                404,
+                "Please don't repeatedly request this endpoint before it unlocks!".to_string(),
+            ),
+            (
+                400,
+                "Puzzle inputs differ by user.  Please log in to get your puzzle input."
+                    .to_string(),
+            ),
+        ]);
+        let mut client = Client::new("secret")
+            .with_base_url(&server.url)
+            .with_delay(Duration::from_millis(50));
+
+        let start = Instant::now();
+        assert_eq!(client.input(2023, 1).unwrap(), "1abc2\n");
+        assert!(matches!(
+            client.input(2023, 2),
+            Err(ClientError::Locked { year: 2023, day: 2 })
+        ));
+        assert!(matches!(
+            client.input(2023, 3),
+            Err(ClientError::NotLoggedIn)
+        ));
+        assert!(start.elapsed() >= Duration::from_millis(100));
+
+        let requests = server.finish();
+        assert_eq!(requests[0].request_line, "GET /2023/day/1/input HTTP/1.1");
+        assert_eq!(requests[0].header("cookie"), Some("session=secret"));
+        let expected = user_agent(contact().as_deref());
+        assert_eq!(requests[0].header("user-agent"), Some(expected.as_str()));
+        assert_eq!(requests[0].body, "");
+    }
+
+    #[test]
+    fn test_session_errors() {
+        let server = MockServer::start(vec![
+            (500, "Internal Server Error".to_string()),
+            (500, "Internal Server Error".to_string()),
+            (400, "Bad Request".to_string()),
+        ]);
+        let mut malformed = Client::new("not a token")
+            .with_base_url(&server.url)
+            .with_delay(Duration::ZERO);
+        assert!(matches!(
+            malformed.input(2023, 1),
+            Err(ClientError::NotLoggedIn)
+        ));
+        // With a token that looks right, a failing server is just a failing server
+        let mut client = Client::new("53616c7465645f5f")
+            .with_base_url(&server.url)
+            .with_delay(Duration::ZERO);
+        assert!(matches!(
+            client.input(2023, 1),
+            Err(ClientError::Http { status: 500, .. })
+        ));
+        assert!(matches!(
+            client.answer(2023, 1, 1, "42"),
+            Err(ClientError::Http { status: 400, .. })
+        ));
+        server.finish();
+    }
+
+    #[test]
+    fn test_user_agent() {
+        assert!(user_agent(Some("me@example.com")).contains("(+me@example.com; "));
+        assert!(user_agent(None).starts_with("advent-of-code-2023/"));
+    }
+}
@@ -1,126 +1,188 @@
-use aoc_runner_derive::aoc;
+use aoc_runner_derive::{aoc, aoc_generator};
+
+use crate::answer::Answer;
+use crate::parse::{lines, Line, ParseError};
+use crate::solution::{Solution, SolveError};
 
 fn match_spelled_out_digit(idx: usize, len: usize, line: &str) -> Option<i32> {
-    if idx <= (len - 3) && len > 3 {
-        let sl = &line[idx..idx + 3];
-        match sl {
-            "one" => return Some(1),
-            "two" => return Some(2),
-            "six" => return Some(6),
+    if len >= 3 && idx <= (len - 3) {
+        match line.get(idx..idx + 3) {
+            Some("one") => return Some(1),
+            Some("two") => return Some(2),
+            Some("six") => return Some(6),
             _ => (),
         }
     }
-    if idx <= (len - 4) && len > 4 {
-        let sl = &line[idx..idx + 4];
-        match sl {
-            "four" => return Some(4),
-            "five" => return Some(5),
-            "nine" => return Some(9),
+    if len >= 4 && idx <= (len - 4) {
+        match line.get(idx..idx + 4) {
+            Some("four") => return Some(4),
+            Some("five") => return Some(5),
+            Some("nine") => return Some(9),
             _ => (),
         }
     }
-    if idx <= (len - 5) && len > 5 {
-        let sl = &line[idx..idx + 5];
-        match sl {
-            "eight" => return Some(8),
-            "seven" => return Some(7),
-            "three" => return Some(3),
+    if len >= 5 && idx <= (len - 5) {
+        match line.get(idx..idx + 5) {
+            Some("eight") => return Some(8),
+            Some("seven") => return Some(7),
+            Some("three") => return Some(3),
             _ => (),
         }
     }
     None
 }
 
-fn get_last_digit_part2(line: &str) -> i32 {
+fn get_last_digit_part2(line: &str) -> Option<i32> {
     let len = line.len();
-    let mut idx = len - 1;
-    for c in line.chars().rev() {
-        if c.is_numeric() {
-            return c.to_digit(10).unwrap() as i32;
+    for (idx, c) in line.char_indices().rev() {
+        if let Some(digit) = c.to_digit(10) {
+            return Some(digit as i32);
         }
         if let Some(value) = match_spelled_out_digit(idx, len, line) {
-            return value;
+            return Some(value);
         }
-        idx -= 1;
     }
-    panic!("No value for line")
+    None
 }
 
-fn get_first_digit_part2(line: &str) -> i32 {
+fn get_first_digit_part2(line: &str) -> Option<i32> {
     let len = line.len();
-    for (idx, c) in line.chars().enumerate() {
-        if c.is_numeric() {
-            return c.to_digit(10).unwrap() as i32;
+    for (idx, c) in line.char_indices() {
+        if let Some(digit) = c.to_digit(10) {
+            return Some(digit as i32);
         }
         if let Some(value) = match_spelled_out_digit(idx, len, line) {
-            return value;
+            return Some(value);
         }
     }
-    panic!("No value for line")
+    None
 }
 
-fn to_calibration_value_part2(line: &str) -> i32 {
-    let first = get_first_digit_part2(line);
-    let last = get_last_digit_part2(line);
-    let value = format!("{}{}", first, last);
-    value.parse::<i32>().unwrap()
+fn to_calibration_value_part2(line: Line) -> Result<i32, ParseError> {
+    let first = get_first_digit_part2(line.text);
+    let last = get_last_digit_part2(line.text);
+    match (first, last) {
+        (Some(first), Some(last)) => Ok(first * 10 + last),
+        _ => Err(line.error_line("expected a digit or spelled out digit")),
+    }
+}
+
+#[aoc_generator(day1, part2)]
+fn parse_calibration_values_part2(content: &str) -> Result<Vec<i32>, ParseError> {
+    lines(1, content).map(to_calibration_value_part2).collect()
 }
 
 #[aoc(day1, part2)]
-fn part2(content: &str) -> i32 {
-    content
-        .lines()
-        .map(|line: &str| to_calibration_value_part2(line))
-        .sum()
+fn part2(values: &[i32]) -> i32 {
+    values.iter().sum()
     // 53312
 }
 
-fn to_calibration_value(line: &str) -> i32 {
-    let first = line.chars().filter(|c| c.is_numeric()).next().unwrap();
-    let last = line
-        .chars()
-        .rev()
-        .filter(|c| c.is_numeric())
-        .next()
-        .unwrap();
-    let value = format!("{}{}", first, last);
-    value.parse::<i32>().unwrap()
+fn to_calibration_value(line: Line) -> Result<i32, ParseError> {
+    let first = line.text.chars().find_map(|c| c.to_digit(10));
+    let last = line.text.chars().rev().find_map(|c| c.to_digit(10));
+    match (first, last) {
+        (Some(first), Some(last)) => Ok((first * 10 + last) as i32),
+        _ => Err(line.error_line("expected a digit")),
+    }
+}
+
+#[aoc_generator(day1, part1)]
+fn parse_calibration_values(content: &str) -> Result<Vec<i32>, ParseError> {
+    lines(1, content).map(to_calibration_value).collect()
 }
 
 #[aoc(day1, part1)]
-fn part1(content: &str) -> i32 {
-    content
-        .lines()
-        .map(|line: &str| to_calibration_value(line))
-        .sum()
+fn part1(values: &[i32]) -> i32 {
+    values.iter().sum()
     // 53386
 }
 
+/// Part 1 and part 2 read the calibration values differently, each may fail on its own
+pub struct Day01;
+
+impl Solution for Day01 {
+    type Input = (Result<Vec<i32>, ParseError>, Result<Vec<i32>, ParseError>);
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Ok((
+            parse_calibration_values(input),
+            parse_calibration_values_part2(input),
+        ))
+    }
+
+    fn part1((values, _): &Self::Input) -> Result<Answer, SolveError> {
+        Ok(part1(values.as_ref().map_err(Clone::clone)?).into())
+    }
+
+    fn part2((_, values): &Self::Input) -> Result<Answer, SolveError> {
+        Ok(part2(values.as_ref().map_err(Clone::clone)?).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
-    use super::*;
-
-    const INPUT: &str = "1abc2
-    pqr3stu8vwx
-    a1b2c3d4e5f
-    treb7uchet";
+    use proptest::prelude::*;
 
-    const INPUT2: &str = "two1nine
-    eightwothree
-    abcone2threexyz
-    xtwone3four
-    4nineeightseven2
-    zoneight234
-    7pqrstsixteen";
+    use super::*;
+    use crate::examples::{answer, example};
 
     #[test]
     fn test_part_1() {
-        assert_eq!(part1(&INPUT), 142);
+        let values = parse_calibration_values(&example(1, "part1")).unwrap();
+        assert_eq!(part1(&values).to_string(), answer(1, "part1"));
     }
 
     #[test]
     fn test_part_2() {
-        assert_eq!(part2(&INPUT2), 281);
+        let values = parse_calibration_values_part2(&example(1, "part2")).unwrap();
+        assert_eq!(part2(&values).to_string(), answer(1, "part2"));
+    }
+
+    #[test]
+    fn test_missing_digit() {
+        let error = parse_calibration_values("1abc2\npqrstu").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+        assert!(parse_calibration_values_part2("1abc2\nab").is_err());
+    }
+
+    #[test]
+    fn test_spelled_out_line() {
+        // The digit is the whole line, or ends right at the end of it
+        let values = parse_calibration_values_part2("one\nseven\n2eight\nfour5six").unwrap();
+        assert_eq!(values, vec![11, 77, 28, 46]);
+    }
+
+    const SPELLED_OUT: [&str; 9] = [
+        "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+    ];
+
+    proptest! {
+        #[test]
+        fn test_first_and_last_digit(line in "[a-z]{0,5}[0-9][a-z0-9]{0,10}") {
+            let digits: Vec<i32> = line
+                .chars()
+                .filter_map(|c| c.to_digit(10))
+                .map(|d| d as i32)
+                .collect();
+            let expected = digits[0] * 10 + digits[digits.len() - 1];
+            prop_assert_eq!(parse_calibration_values(&line).unwrap(), vec![expected]);
+        }
+
+        #[test]
+        fn test_spelled_out_digits(digits in prop::collection::vec(1..=9usize, 1..8)) {
+            let line: String = digits.iter().map(|d| d.to_string()).collect();
+            // 'q' doesn't occur in any spelled out digit, so it can't form a new one
+            let spelled = digits
+                .iter()
+                .map(|&d| SPELLED_OUT[d - 1])
+                .collect::<Vec<_>>()
+                .join("q");
+            prop_assert_eq!(
+                parse_calibration_values_part2(&spelled).unwrap(),
+                parse_calibration_values(&line).unwrap()
+            );
+        }
     }
 }
@@ -1,76 +1,248 @@
-use aoc_runner_derive::aoc;
+use std::collections::BTreeMap;
 
-#[aoc(day2, part2)]
-fn part2(content: &str) -> i32 {
-    content
-        .lines()
-        .map(|line| line.split_once(": ").unwrap().1)
-        .map(|line: &str| {
-            let rgb_max = get_rgb_max(line);
-            rgb_max.0 * rgb_max.1 * rgb_max.2
-        })
-        .sum()
-    // 63711
+use aoc_runner_derive::{aoc, aoc_generator};
+
+use crate::answer::Answer;
+use crate::parse::{lines, Line, ParseError};
+use crate::solution::{Solution, SolveError};
+
+/// Number of cubes per color, of a reveal or in a bag
+pub type Cubes = BTreeMap<String, i32>;
+
+/// The colors of the puzzle, in the order of the bag of part 1
+const COLORS: [&str; 3] = ["red", "green", "blue"];
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Game {
+    pub id: i32,
+    pub reveals: Vec<Cubes>,
 }
 
-fn to_rgb(reveal: &str) -> (i32, i32, i32) {
-    let reveal_ = reveal.strip_prefix(" ").or_else(|| Some(reveal)).unwrap();
-    let (num_cubes_, color) = reveal_.split_once(' ').unwrap();
-    let num_cubes = num_cubes_.parse::<i32>().unwrap();
-    match color {
-        "red" => (num_cubes, 0, 0),
-        "green" => (0, num_cubes, 0),
-        "blue" => (0, 0, num_cubes),
-        _ => panic!("uhoh"),
+impl Game {
+    /// Whether the bag has enough cubes of every color for each reveal
+    pub fn is_possible(&self, bag: &Cubes) -> bool {
+        self.reveals.iter().flatten().all(|(color, count)| {
+            let available = bag.get(color).copied().unwrap_or(0);
+            *count <= available
+        })
+    }
+
+    /// The fewest cubes of each revealed color that make the game possible
+    pub fn minimal_bag(&self) -> Cubes {
+        let mut bag = Cubes::new();
+        for (color, count) in self.reveals.iter().flatten() {
+            let most = bag.entry(color.clone()).or_insert(0);
+            *most = (*most).max(*count);
+        }
+        bag
+    }
+
+    /// The product of the cubes of the colors in the minimal bag, a color never revealed counts 0
+    pub fn power(&self, colors: &[&str]) -> i32 {
+        let bag = self.minimal_bag();
+        let count = |color: &&str| bag.get(*color).copied().unwrap_or(0);
+        colors.iter().map(count).product()
     }
 }
 
-fn get_rgb_max(reveals_: &str) -> (i32, i32, i32) {
-    let reveals: Vec<&str> = reveals_.split(&[',', ';']).collect();
-    let rgb_vectors: Vec<(i32, i32, i32)> = reveals.iter().map(|reveal| to_rgb(reveal)).collect();
-    rgb_vectors
-        .iter()
-        .copied()
-        .reduce(|a: (i32, i32, i32), b: (i32, i32, i32)| (a.0.max(b.0), a.1.max(b.1), a.2.max(b.2)))
-        .unwrap()
+/// The games that could have been played with the bag
+pub fn possible_games<'a>(games: &'a [Game], bag: &'a Cubes) -> impl Iterator<Item = &'a Game> {
+    games.iter().filter(|game| game.is_possible(bag))
 }
-fn get_id_if_valid_game(line: &str) -> i32 {
-    let (game_id_, reveals) = line.split_once(": ").unwrap();
-    let rgb_max = get_rgb_max(reveals);
-    if rgb_max.0 <= 12 && rgb_max.1 <= 13 && rgb_max.2 <= 14 {
-        (&game_id_[5..]).parse::<i32>().unwrap()
-    } else {
-        0
+
+fn parse_reveal(line: &Line, reveal: &str) -> Result<Cubes, ParseError> {
+    let mut cubes = Cubes::new();
+    for cube in reveal.split(',') {
+        let cube_ = cube.strip_prefix(' ').unwrap_or(cube);
+        let (num_cubes_, color) = line.split_once(cube_, " ")?;
+        let num_cubes = line.parse::<i32>(num_cubes_, "a number of cubes")?;
+        if color.is_empty() || !color.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(line.error(color, "expected a color"));
+        }
+        if cubes.insert(color.to_string(), num_cubes).is_some() {
+            return Err(line.error(color, "expected every color once per reveal"));
+        }
     }
+    Ok(cubes)
+}
+
+fn parse_game(line: Line) -> Result<Game, ParseError> {
+    let (game_id_, reveals_) = line.split_once(line.text, ": ")?;
+    let game_id = line.strip_prefix(game_id_, "Game ")?;
+    let reveals = reveals_
+        .split(';')
+        .map(|reveal| parse_reveal(&line, reveal))
+        .collect::<Result<_, _>>()?;
+    Ok(Game {
+        id: line.parse::<i32>(game_id, "a game id")?,
+        reveals,
+    })
+}
+
+#[aoc_generator(day2)]
+fn parse_games(content: &str) -> Result<Vec<Game>, ParseError> {
+    lines(2, content).map(parse_game).collect()
+}
+
+#[aoc(day2, part2)]
+fn part2(games: &[Game]) -> i32 {
+    games.iter().map(|game| game.power(&COLORS)).sum()
+    // 63711
 }
 
 #[aoc(day2, part1)]
-fn part1(content: &str) -> i32 {
-    content
-        .lines()
-        .map(|line: &str| get_id_if_valid_game(line))
-        .sum()
+fn part1(games: &[Game]) -> i32 {
+    let bag: Cubes = COLORS
+        .iter()
+        .zip([12, 13, 14])
+        .map(|(color, count)| (color.to_string(), count))
+        .collect();
+    possible_games(games, &bag).map(|game| game.id).sum()
     // 2439
 }
 
+pub struct Day02;
+
+impl Solution for Day02 {
+    type Input = Vec<Game>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_games(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(part1(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(part2(input).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
-    use super::*;
+    use proptest::prelude::*;
 
-    const INPUT: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
-Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
-Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
-Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
-Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+    use super::*;
+    use crate::examples::{answer, example};
 
     #[test]
     fn test_part_1() {
-        assert_eq!(part1(&INPUT), 8);
+        let input = parse_games(&example(2, "part1")).unwrap();
+        assert_eq!(part1(&input).to_string(), answer(2, "part1"));
     }
 
     #[test]
     fn test_part_2() {
-        assert_eq!(part2(&INPUT), 2286);
+        let input = parse_games(&example(2, "part1")).unwrap();
+        assert_eq!(part2(&input).to_string(), answer(2, "part2"));
+    }
+
+    fn bag(cubes: &[(&str, i32)]) -> Cubes {
+        cubes.iter().map(|(c, n)| (c.to_string(), *n)).collect()
+    }
+
+    #[test]
+    fn test_game() {
+        let games = parse_games(&example(2, "part1")).unwrap();
+        assert_eq!(games[0].reveals.len(), 3);
+        assert_eq!(
+            games[0].reveals[1],
+            bag(&[("red", 1), ("green", 2), ("blue", 6)])
+        );
+        assert_eq!(
+            games[2].minimal_bag(),
+            bag(&[("red", 20), ("green", 13), ("blue", 6)])
+        );
+        assert_eq!(games[2].power(&COLORS), 1560);
+
+        let ids = |bag: &Cubes| {
+            possible_games(&games, bag)
+                .map(|g| g.id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            ids(&bag(&[("red", 12), ("green", 13), ("blue", 14)])),
+            [1, 2, 5]
+        );
+        assert_eq!(
+            ids(&bag(&[("red", 20), ("green", 13), ("blue", 15)])),
+            [1, 2, 3, 4, 5]
+        );
+        assert_eq!(ids(&bag(&[("red", 20), ("green", 13)])), Vec::<i32>::new());
+
+        let games = parse_games("Game 7: 2 purple, 1 red; 3 purple").unwrap();
+        assert_eq!(games[0].minimal_bag(), bag(&[("purple", 3), ("red", 1)]));
+        assert_eq!(games[0].power(&COLORS), 0);
+        assert_eq!(games[0].power(&["purple", "red"]), 3);
+    }
+
+    #[test]
+    fn test_invalid_color() {
+        let error = parse_games("Game 1: 3 blue, 4 pur-ple").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 19));
+        assert_eq!(error.text, "pur-ple");
+        let error = parse_games("Game 1: 3 blue, 4 blue; 1 red").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 19));
+        assert!(parse_games("Game 1: 3 blue, 4").is_err());
+    }
+
+    fn format_game(game: &Game) -> String {
+        let reveals: Vec<String> = game
+            .reveals
+            .iter()
+            .map(|reveal| {
+                let cubes = reveal.iter().map(|(color, n)| format!("{} {}", n, color));
+                cubes.collect::<Vec<_>>().join(", ")
+            })
+            .collect();
+        format!("Game {}: {}", game.id, reveals.join("; "))
+    }
+
+    fn reveal() -> impl Strategy<Value = Vec<(i32, String)>> {
+        let color = prop_oneof![
+            prop::sample::select(vec!["red", "green", "blue"]).prop_map(String::from),
+            "[a-z]{1,8}",
+        ];
+        prop::collection::btree_map(color, 0..20i32, 1..4)
+            .prop_map(|cubes| cubes.into_iter().map(|(color, n)| (n, color)).collect())
+    }
+
+    proptest! {
+        #[test]
+        fn test_round_trip(id in 1..1000i32, reveals in prop::collection::vec(reveal(), 1..5)) {
+            let line = format!(
+                "Game {}: {}",
+                id,
+                reveals
+                    .iter()
+                    .map(|reveal| {
+                        reveal
+                            .iter()
+                            .map(|(n, color)| format!("{} {}", n, color))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    })
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            );
+            let max = |color: &str| {
+                reveals
+                    .iter()
+                    .flatten()
+                    .filter(|(_, c)| *c == color)
+                    .map(|(n, _)| *n)
+                    .max()
+                    .unwrap_or(0)
+            };
+
+            let games = parse_games(&line).unwrap();
+            prop_assert_eq!(games[0].id, id);
+            prop_assert_eq!(games[0].reveals.len(), reveals.len());
+            prop_assert_eq!(games[0].power(&COLORS), max("red") * max("green") * max("blue"));
+            prop_assert!(games[0].is_possible(&games[0].minimal_bag()));
+            prop_assert_eq!(parse_games(&format_game(&games[0])).unwrap(), games);
+        }
     }
 }
@@ -1,178 +1,472 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
-use aoc_runner_derive::aoc;
+use aoc_runner_derive::{aoc, aoc_generator};
 
-struct Point {
-    x: i32,
-    y: i32,
-}
+use crate::answer::Answer;
+use crate::geometry::Point;
+use crate::parse::{lines, Line, ParseError};
+use crate::solution::{Solution, SolveError};
+use crate::svg::{Style, Svg};
 
-struct Number {
-    value: i32,
-    length: i32,
-    root: Point,
+#[derive(Debug, PartialEq)]
+pub struct Number {
+    pub value: i32,
+    pub length: i32,
+    /// Position of the first digit
+    pub root: Point<i32>,
 }
 
-struct Symbol {
-    char: char,
-    point: Point,
+#[derive(Debug, PartialEq)]
+pub struct Symbol {
+    pub char: char,
+    pub point: Point<i32>,
 }
 
 fn parse_and_store_number(
-    line: &str,
+    line: &Line,
     number_root_x: usize,
     length: usize,
     numbers: &mut Vec<Number>,
-    y: usize,
-) {
-    let value = (&line[number_root_x..number_root_x + length])
-        .parse::<i32>()
-        .unwrap();
+) -> Result<(), ParseError> {
+    let value = line.parse::<i32>(
+        &line.text[number_root_x..number_root_x + length],
+        "a part number",
+    )?;
     numbers.push(Number {
-        value: value,
+        value,
         length: length as i32,
-        root: Point {
-            x: number_root_x as i32,
-            y: y as i32,
-        },
+        root: Point::new(number_root_x as i32, line.number as i32 - 1),
     });
+    Ok(())
 }
 
-fn parse_numbers_and_symbols(content: &str) -> (Vec<Number>, Vec<Symbol>) {
+#[aoc_generator(day3)]
+fn parse_numbers_and_symbols(content: &str) -> Result<Schematic, ParseError> {
     let mut parsing_number: bool;
     let mut number_root_x: usize;
     let mut numbers: Vec<Number> = Vec::new();
     let mut symbols: Vec<Symbol> = Vec::new();
-    for (y, line) in content.lines().enumerate() {
+    for line in lines(3, content) {
+        let y = line.number - 1;
         parsing_number = false;
         number_root_x = 0;
-        for (x, ch) in line.chars().enumerate() {
-            if ch.is_numeric() {
+        for (x, ch) in line.text.char_indices() {
+            if ch.is_ascii_digit() {
                 if !parsing_number {
                     parsing_number = true;
                     number_root_x = x;
                 }
             } else if parsing_number {
                 let length = x - number_root_x;
-                parse_and_store_number(line, number_root_x, length, &mut numbers, y);
+                parse_and_store_number(&line, number_root_x, length, &mut numbers)?;
                 parsing_number = false;
             }
-            if !ch.is_numeric() && ch != '.' {
+            if !ch.is_ascii_digit() && ch != '.' {
+                if ch.is_whitespace() || ch.is_control() {
+                    return Err(line.error(&line.text[x..], "expected a digit, '.' or symbol"));
+                }
                 symbols.push(Symbol {
                     char: ch,
-                    point: Point {
-                        x: x as i32,
-                        y: y as i32,
-                    },
+                    point: Point::new(x as i32, y as i32),
                 });
             }
         }
         if parsing_number {
-            let length = line.len() - number_root_x;
-            parse_and_store_number(line, number_root_x, length, &mut numbers, y);
+            let length = line.text.len() - number_root_x;
+            parse_and_store_number(&line, number_root_x, length, &mut numbers)?;
         }
     }
-    (numbers, symbols)
+    Ok(Schematic::new(numbers, symbols))
+}
+
+/// The numbers and symbols occupying each cell of the schematic, to find neighbours without
+/// comparing every number with every symbol
+#[derive(Debug)]
+struct Index {
+    numbers: HashMap<Point<i32>, usize>,
+    symbols: HashMap<Point<i32>, usize>,
 }
 
-fn number_near_symbol(number: &Number, symbol: &Symbol) -> bool {
-    /* There should be a way to calculate this instead of doing a lame loop... eh */
-    let (sx, sy) = (symbol.point.x, symbol.point.y);
-    for i in 0..number.length {
-        let digit = Point {
-            x: number.root.x + i,
-            y: number.root.y,
+impl Index {
+    fn new(numbers: &[Number], symbols: &[Symbol]) -> Index {
+        let mut index = Index {
+            numbers: HashMap::new(),
+            symbols: HashMap::new(),
         };
-        if {
-            ((sx + 1) >= digit.x && digit.x >= (sx - 1))
-                && ((sy + 1) >= digit.y && digit.y >= (sy - 1))
-        } {
-            return true;
+        for (i, number) in numbers.iter().enumerate() {
+            for x in 0..number.length {
+                index.numbers.insert(number.root + Point::new(x, 0), i);
+            }
+        }
+        for (i, symbol) in symbols.iter().enumerate() {
+            index.symbols.insert(symbol.point, i);
         }
+        index
+    }
+
+    /// Indices of the numbers with a digit next to the symbol, in the order of the schematic
+    fn numbers_near(&self, symbol: &Symbol) -> Vec<usize> {
+        let mut near: Vec<usize> = symbol
+            .point
+            .surrounding()
+            .iter()
+            .filter_map(|point| self.numbers.get(point).copied())
+            .collect();
+        near.sort();
+        near.dedup();
+        near
+    }
+
+    /// Indices of the symbols next to any digit of the number, in the order of the schematic
+    fn symbols_near(&self, number: &Number) -> Vec<usize> {
+        let mut near: Vec<usize> = (-1..=number.length)
+            .flat_map(|x| (-1..=1).map(move |y| number.root + Point::new(x, y)))
+            .filter_map(|point| self.symbols.get(&point).copied())
+            .collect();
+        near.sort();
+        near
     }
-    false
 }
 
-#[aoc(day3, part1)]
-fn part1(content: &str) -> i32 {
-    let (numbers, symbols): (Vec<Number>, Vec<Symbol>) = parse_numbers_and_symbols(content);
-
-    let mut sum = 0;
-    for number in numbers.iter() {
-        for symbol in symbols.iter() {
-            if number_near_symbol(number, symbol) {
-                sum += number.value;
-                break;
-            }
+/// Which symbols count as gears, and how many numbers they connect
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GearRule {
+    pub symbol: char,
+    /// The exact number of adjacent numbers
+    pub arity: usize,
+    /// Whether a number can be part of several gears. Otherwise symbols are tried in order, and
+    /// numbers of an earlier gear don't count for the later ones.
+    pub share_numbers: bool,
+}
+
+impl Default for GearRule {
+    /// The rule of the puzzle, a `*` next to exactly two numbers
+    fn default() -> GearRule {
+        GearRule {
+            symbol: '*',
+            arity: 2,
+            share_numbers: true,
         }
     }
-    sum
-    // 528799
 }
 
-#[aoc(day3, part2)]
-fn part2(content: &str) -> i32 {
-    let (numbers, symbols): (Vec<Number>, Vec<Symbol>) = parse_numbers_and_symbols(content);
-
-    let mut sum = 0;
-    let mut points_used: HashSet<(i32, i32)> = HashSet::new();
-    let mut ratio_numbers: Vec<&Number> = Vec::new();
-    for symbol in symbols.iter() {
-        if symbol.char != '*' {
-            continue;
+/// The numbers and symbols of an engine schematic
+#[derive(Debug)]
+pub struct Schematic {
+    pub numbers: Vec<Number>,
+    pub symbols: Vec<Symbol>,
+    index: Index,
+}
+
+impl Schematic {
+    pub fn new(numbers: Vec<Number>, symbols: Vec<Symbol>) -> Schematic {
+        let index = Index::new(&numbers, &symbols);
+        Schematic {
+            numbers,
+            symbols,
+            index,
         }
+    }
+
+    /// The numbers next to the symbol, in the order of the schematic
+    pub fn numbers_near(&self, symbol: &Symbol) -> Vec<&Number> {
+        let near = self.index.numbers_near(symbol).into_iter();
+        near.map(|i| &self.numbers[i]).collect()
+    }
+
+    /// Every symbol with all the numbers next to it
+    pub fn report(&self) -> Vec<(&Symbol, Vec<&Number>)> {
+        let symbols = self.symbols.iter();
+        symbols
+            .map(|symbol| (symbol, self.numbers_near(symbol)))
+            .collect()
+    }
 
-        ratio_numbers.clear();
-        for number in numbers.iter() {
-            if points_used.contains(&(number.root.x, number.root.y)) {
+    /// Numbers next to at least one symbol
+    pub fn part_numbers(&self) -> impl Iterator<Item = &Number> {
+        let numbers = self.numbers.iter();
+        numbers.filter(|number| !self.index.symbols_near(number).is_empty())
+    }
+
+    /// The symbols that are gears by the rule, with their numbers
+    pub fn gears(&self, rule: &GearRule) -> Vec<(&Symbol, Vec<&Number>)> {
+        let mut used: HashSet<Point<i32>> = HashSet::new();
+        let mut gears = Vec::new();
+        for (symbol, numbers) in self.report() {
+            if symbol.char != rule.symbol {
                 continue;
             }
-            if number_near_symbol(number, symbol) {
-                ratio_numbers.push(number);
-                if ratio_numbers.len() == 3 {
-                    break;
-                }
+            let numbers: Vec<&Number> = numbers
+                .into_iter()
+                .filter(|number| rule.share_numbers || !used.contains(&number.root))
+                .collect();
+            if numbers.len() != rule.arity {
+                continue;
             }
+            used.extend(numbers.iter().map(|number| number.root));
+            gears.push((symbol, numbers));
         }
+        gears
+    }
+}
 
-        if ratio_numbers.len() != 2 {
-            continue;
+#[aoc(day3, part1)]
+fn part1(schematic: &Schematic) -> i64 {
+    schematic
+        .part_numbers()
+        .map(|number| number.value as i64)
+        .sum()
+    // 528799
+}
+
+#[aoc(day3, part2)]
+fn part2(schematic: &Schematic) -> i128 {
+    schematic
+        .gears(&GearRule::default())
+        .iter()
+        .map(|(_, numbers)| {
+            numbers
+                .iter()
+                .map(|number| number.value as i128)
+                .product::<i128>()
+        })
+        .sum()
+    // 84907174
+}
+
+const CELL: f64 = 14.0;
+
+/// Draw the schematic as SVG, with part numbers in green, other numbers in red and gears in yellow
+pub fn svg(schematic: &Schematic) -> Svg {
+    let Schematic {
+        numbers, symbols, ..
+    } = schematic;
+    let width = numbers
+        .iter()
+        .map(|n| n.root.x + n.length)
+        .chain(symbols.iter().map(|s| s.point.x + 1))
+        .max()
+        .unwrap_or(0);
+    let height = numbers
+        .iter()
+        .map(|n| n.root.y)
+        .chain(symbols.iter().map(|s| s.point.y))
+        .max()
+        .map_or(0, |y| y + 1);
+    let mut svg = Svg::new(width as f64 * CELL, height as f64 * CELL);
+    svg.rect(
+        0.0,
+        0.0,
+        width as f64 * CELL,
+        height as f64 * CELL,
+        Style::fill("white"),
+    );
+    let text = Style::fill("black").with_font_size(11.0);
+    let cell = |svg: &mut Svg, point: Point<i32>, char: char, color: &str| {
+        let (x, y) = (point.x as f64 * CELL, point.y as f64 * CELL);
+        svg.rect(x, y, CELL, CELL, Style::fill(color));
+        svg.text(x + CELL / 2.0, y + CELL / 2.0, &char.to_string(), text);
+    };
+
+    let gears = schematic.gears(&GearRule::default());
+    for number in numbers {
+        let is_part = !schematic.index.symbols_near(number).is_empty();
+        let color = if is_part { "#9be39b" } else { "#f4b6b6" };
+        let digits = format!("{:0width$}", number.value, width = number.length as usize);
+        for (i, digit) in digits.chars().enumerate() {
+            cell(
+                &mut svg,
+                number.root + Point::new(i as i32, 0),
+                digit,
+                color,
+            );
         }
-        sum += ratio_numbers
-            .iter()
-            .map(|number| {
-                points_used.insert((number.root.x, number.root.y));
-                number.value
-            })
-            .product::<i32>();
     }
-    sum
-    // 84907174
+    for symbol in symbols {
+        let color = if gears.iter().any(|(gear, _)| *gear == symbol) {
+            "#ffd54f"
+        } else {
+            "#cfe3ff"
+        };
+        cell(&mut svg, symbol.point, symbol.char, color);
+    }
+    svg
+}
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    type Input = Schematic;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_numbers_and_symbols(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(part1(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(part2(input).into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
 
-    use super::*;
+    use proptest::prelude::*;
 
-    const INPUT: &str = "467..114..
-...*......
-..35..633.
-......#...
-617*......
-.....+.58.
-..592.....
-......755.
-...$.*....
-.664.598..";
+    use super::*;
+    use crate::examples::{answer, example};
+    use crate::reference::solve;
+    use crate::svg::assert_golden;
 
     #[test]
     fn test_part_1() {
-        assert_eq!(part1(&INPUT), 4361);
+        let input = parse_numbers_and_symbols(&example(3, "part1")).unwrap();
+        assert_eq!(part1(&input).to_string(), answer(3, "part1"));
     }
 
     #[test]
     fn test_part_2() {
-        assert_eq!(part2(&INPUT), 467835);
+        let input = parse_numbers_and_symbols(&example(3, "part1")).unwrap();
+        assert_eq!(part2(&input).to_string(), answer(3, "part2"));
+    }
+
+    #[test]
+    fn test_schematic() {
+        let schematic = parse_numbers_and_symbols(&example(3, "part1")).unwrap();
+        let report = schematic.report();
+        assert_eq!(report.len(), 6);
+        let values = |numbers: &[&Number]| numbers.iter().map(|n| n.value).collect::<Vec<_>>();
+        assert_eq!(
+            (report[0].0.char, values(&report[0].1)),
+            ('*', vec![467, 35])
+        );
+        assert_eq!((report[2].0.char, values(&report[2].1)), ('*', vec![617]));
+        assert_eq!(schematic.part_numbers().count(), 8);
+
+        let gears = schematic.gears(&GearRule::default());
+        assert_eq!(gears.len(), 2);
+        let rule = GearRule {
+            symbol: '*',
+            arity: 1,
+            share_numbers: true,
+        };
+        assert_eq!(values(&schematic.gears(&rule)[0].1), vec![617]);
+        let rule = GearRule {
+            symbol: '#',
+            ..rule
+        };
+        assert_eq!(values(&schematic.gears(&rule)[0].1), vec![633]);
+    }
+
+    #[test]
+    fn test_shared_numbers() {
+        let without_sharing = GearRule {
+            share_numbers: false,
+            ..GearRule::default()
+        };
+        // 2 is next to both gears, the second one only counts when numbers can be shared
+        let input = "3*.*4\n..2..";
+        let schematic = parse_numbers_and_symbols(input).unwrap();
+        assert_eq!(schematic.gears(&GearRule::default()).len(), 2);
+        assert_eq!(schematic.gears(&without_sharing).len(), 1);
+        assert_eq!(part2(&schematic), 14);
+        assert_eq!(solve(3, 2, input), Some(Answer::from(14)));
+        // A * with 3 numbers is no gear, even when one of them is part of another gear
+        let input = "3*.*4\n..2.5";
+        let schematic = parse_numbers_and_symbols(input).unwrap();
+        assert_eq!(schematic.gears(&GearRule::default()).len(), 1);
+        assert_eq!(schematic.gears(&without_sharing).len(), 2);
+        assert_eq!(part2(&schematic), 6);
+        assert_eq!(solve(3, 2, input), Some(Answer::from(6)));
+    }
+
+    #[test]
+    fn test_large_numbers() {
+        // The gear ratio doesn't fit in an i32
+        let input = "2600000.\n*826#0.#\n........\n99999999\n*.......";
+        let schematic = parse_numbers_and_symbols(input).unwrap();
+        assert_eq!(part1(&schematic), 2_600_000 + 826 + 99_999_999);
+        assert_eq!(part2(&schematic), 2_600_000 * 826);
+    }
+
+    #[test]
+    fn test_svg() {
+        let schematic = parse_numbers_and_symbols(&example(3, "part1")).unwrap();
+        assert_golden(3, "part1", &svg(&schematic));
+    }
+
+    #[test]
+    fn test_invalid_part_number() {
+        let error = parse_numbers_and_symbols("467..114..\n...*99999999999").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 5));
+        assert_eq!(error.text, "99999999999");
+    }
+
+    /// Compare every digit of the number with the symbol, like the solution used to
+    fn number_near_symbol(number: &Number, symbol: &Symbol) -> bool {
+        (0..number.length)
+            .map(|i| number.root + Point::new(i, 0))
+            .any(|digit| digit.chebyshev(&symbol.point) <= 1)
+    }
+
+    fn pad(rows: &[String]) -> String {
+        let border = ".".repeat(rows[0].len() + 2);
+        let padded: Vec<String> = rows.iter().map(|row| format!(".{}.", row)).collect();
+        format!("{}\n{}\n{}", border, padded.join("\n"), border)
+    }
+
+    #[test]
+    fn test_padding_large_numbers() {
+        for rows in [["#.3000##", "#*800000"], ["1000000*", ".*.1100*"]] {
+            let rows = rows.map(String::from);
+            let schematic = parse_numbers_and_symbols(&rows.join("\n")).unwrap();
+            let padded = parse_numbers_and_symbols(&pad(&rows)).unwrap();
+            assert_eq!(part2(&padded), part2(&schematic), "{:?}", rows);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_padding_invariance(rows in prop::collection::vec("[.0-9*#+$]{8}", 1..8)) {
+            let schematic = parse_numbers_and_symbols(&rows.join("\n")).unwrap();
+            let padded = parse_numbers_and_symbols(&pad(&rows)).unwrap();
+            prop_assert_eq!(part1(&padded), part1(&schematic));
+            prop_assert_eq!(part2(&padded), part2(&schematic));
+        }
+
+        #[test]
+        fn test_index(rows in prop::collection::vec("[.0-9*#]{8}", 1..8)) {
+            let Schematic { numbers, symbols, index } =
+                parse_numbers_and_symbols(&rows.join("\n")).unwrap();
+            for (i, number) in numbers.iter().enumerate() {
+                let near: Vec<usize> = (0..symbols.len())
+                    .filter(|s| number_near_symbol(number, &symbols[*s]))
+                    .collect();
+                prop_assert_eq!(index.symbols_near(number), near);
+                for (s, symbol) in symbols.iter().enumerate() {
+                    let found = index.numbers_near(symbol).contains(&i);
+                    prop_assert_eq!(found, number_near_symbol(number, symbol), "{} {}", i, s);
+                }
+            }
+        }
+
+        #[test]
+        fn test_reference(rows in prop::collection::vec("[.0-9*#]{8}", 1..8)) {
+            // Dense schematics, where numbers are often next to several gears
+            let input = rows.join("\n");
+            let schematic = parse_numbers_and_symbols(&input).unwrap();
+            prop_assert_eq!(Some(Answer::from(part1(&schematic))), solve(3, 1, &input));
+            prop_assert_eq!(Some(Answer::from(part2(&schematic))), solve(3, 2, &input));
+        }
+
+        #[test]
+        fn test_without_symbols(rows in prop::collection::vec("[.0-9]{8}", 1..8)) {
+            let schematic = parse_numbers_and_symbols(&rows.join("\n")).unwrap();
+            prop_assert_eq!(part1(&schematic), 0);
+            prop_assert_eq!(part2(&schematic), 0);
+        }
     }
 }
@@ -1,33 +1,45 @@
 use std::collections::HashSet;
 
-use aoc_runner_derive::aoc;
-
-fn get_matches(card_line: &str) -> usize {
-    card_line
-        .split_once(':')
-        .unwrap()
-        .1
-        .split(" | ")
-        .map(|c: &str| -> HashSet<i32> {
-            HashSet::from_iter(c.split(" ").filter_map(|c| c.parse::<i32>().ok()))
-        })
-        .reduce(|acc, e| &acc & &e)
-        .unwrap()
-        .len() as usize
+use aoc_runner_derive::{aoc, aoc_generator};
+
+use crate::answer::Answer;
+use crate::parse::{lines, Line, ParseError};
+use crate::solution::{Solution, SolveError};
+
+fn parse_numbers(line: &Line, numbers: &str) -> Result<HashSet<i32>, ParseError> {
+    numbers
+        .split(' ')
+        .filter(|n| !n.is_empty())
+        .map(|n| line.parse::<i32>(n, "a number"))
+        .collect()
+}
+
+fn get_matches(card_line: Line) -> Result<usize, ParseError> {
+    let (_, numbers) = card_line.split_once(card_line.text, ":")?;
+    let (winning, have) = card_line.split_once(numbers, " | ")?;
+    let winning = parse_numbers(&card_line, winning)?;
+    let have = parse_numbers(&card_line, have)?;
+    Ok((&winning & &have).len())
+}
+
+#[aoc_generator(day4)]
+fn parse_cards(content: &str) -> Result<Vec<usize>, ParseError> {
+    lines(4, content).map(get_matches).collect()
 }
 
 #[aoc(day4, part2)]
-fn part2(content: &str) -> u32 {
-    let mut card_instances: Vec<u32> = vec![1u32; content.lines().count() + 1];
+fn part2(matches_per_card: &[usize]) -> u32 {
+    let mut card_instances: Vec<u32> = vec![1u32; matches_per_card.len() + 1];
 
-    for (card_id, card_line) in content.lines().enumerate() {
-        let matches = get_matches(&card_line);
+    for (card_id, &matches) in matches_per_card.iter().enumerate() {
         if matches == 0 {
             continue;
         }
 
         let card_number = card_id + 1;
-        for next_card_number in card_number + 1..=card_number + matches {
+        for next_card_number in
+            card_number + 1..=(card_number + matches).min(card_instances.len() - 1)
+        {
             card_instances[next_card_number] += card_instances[card_number];
         }
     }
@@ -37,35 +49,111 @@ fn part2(content: &str) -> u32 {
 }
 
 #[aoc(day4, part1)]
-fn part1(content: &str) -> u32 {
-    content
-        .lines()
-        .map(get_matches)
-        .filter(|w| w > &0)
-        .map(|w| 2u32.pow(w as u32 - 1))
+fn part1(matches_per_card: &[usize]) -> u32 {
+    matches_per_card
+        .iter()
+        .filter(|w| w > &&0)
+        .map(|w| 2u32.pow(*w as u32 - 1))
         .sum()
     // 21105
 }
 
+pub struct Day04;
+
+impl Solution for Day04 {
+    type Input = Vec<usize>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_cards(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(part1(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(part2(input).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
-    use super::*;
+    use proptest::prelude::*;
 
-    const INPUT: &str = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
-Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
-Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
-Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
-Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
-Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
+    use super::*;
+    use crate::examples::{answer, example};
 
     #[test]
     fn test_part_1() {
-        assert_eq!(part1(&INPUT), 13);
+        let input = parse_cards(&example(4, "part1")).unwrap();
+        assert_eq!(part1(&input).to_string(), answer(4, "part1"));
     }
 
     #[test]
     fn test_part_2() {
-        assert_eq!(part2(&INPUT), 30);
+        let input = parse_cards(&example(4, "part1")).unwrap();
+        assert_eq!(part2(&input).to_string(), answer(4, "part2"));
+    }
+
+    #[test]
+    fn test_invalid_card() {
+        let error = parse_cards("Card 1: 41 48 x3 | 83 86").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 15));
+        assert_eq!(
+            parse_cards("Card 1: 41 48 83 86").unwrap_err().reason,
+            "expected \" | \""
+        );
+    }
+
+    type Card = (Vec<u32>, Vec<u32>);
+
+    fn format_cards(cards: &[Card]) -> String {
+        let join = |numbers: &[u32]| {
+            numbers
+                .iter()
+                .map(|n| format!("{:2}", n))
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        cards
+            .iter()
+            .enumerate()
+            .map(|(i, (winning, have))| {
+                format!("Card {}: {} | {}", i + 1, join(winning), join(have))
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Cards, and the same cards with their numbers shuffled
+    fn cards_and_shuffled() -> impl Strategy<Value = (Vec<Card>, Vec<Card>)> {
+        let card = (
+            prop::collection::vec(1..100u32, 1..10),
+            prop::collection::vec(1..100u32, 1..20),
+        );
+        prop::collection::vec(card, 1..10).prop_flat_map(|cards| {
+            let shuffled: Vec<_> = cards
+                .iter()
+                .map(|(winning, have)| {
+                    (
+                        Just(winning.clone()).prop_shuffle(),
+                        Just(have.clone()).prop_shuffle(),
+                    )
+                })
+                .collect();
+            (Just(cards), shuffled)
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn test_shuffle_invariance((cards, shuffled) in cards_and_shuffled()) {
+            let matches = parse_cards(&format_cards(&cards)).unwrap();
+            let shuffled_matches = parse_cards(&format_cards(&shuffled)).unwrap();
+            prop_assert_eq!(&shuffled_matches, &matches);
+            prop_assert_eq!(part1(&shuffled_matches), part1(&matches));
+            prop_assert_eq!(part2(&shuffled_matches), part2(&matches));
+        }
     }
 }
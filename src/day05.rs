@@ -1,56 +1,86 @@
 use std::ops::Range;
 
-use aoc_runner_derive::aoc;
+use aoc_runner_derive::{aoc, aoc_generator};
 
+use crate::answer::Answer;
+use crate::parse::{end_of_input, lines, next_line, Line, ParseError};
+use crate::solution::{Solution, SolveError};
+
+#[derive(Debug)]
 struct Rule {
     range: Range<i64>,
     addition: i64,
 }
 
-fn make_maps(sections: Vec<&str>) -> Vec<Vec<Rule>> {
-    let mut maps = Vec::new();
-    for section in sections {
-        let mut mapping = Vec::new(); // TODO functional
-        for line in section.lines().skip(1) {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            let dest = parts[0].parse::<i64>().unwrap();
-            let src = parts[1].parse::<i64>().unwrap();
-            let length = parts[2].parse::<i64>().unwrap();
-            mapping.push(Rule {
-                range: src..src + length,
-                addition: dest - src,
-            });
+#[derive(Debug)]
+pub struct Almanac {
+    seeds: Vec<i64>,
+    maps: Vec<Vec<Rule>>,
+}
+
+fn parse_seeds(line: &Line) -> Result<Vec<i64>, ParseError> {
+    let seeds = line.strip_prefix(line.text, "seeds: ")?;
+    if seeds.trim().is_empty() {
+        return Err(line.error(seeds, "expected at least one seed"));
+    }
+    seeds
+        .split_whitespace()
+        .map(|p| line.parse::<i64>(p, "a seed number"))
+        .collect()
+}
+
+fn parse_rule(line: &Line) -> Result<Rule, ParseError> {
+    let parts: Vec<&str> = line.text.split_whitespace().collect();
+    if parts.len() != 3 {
+        return Err(line.error_line("expected destination, source and length"));
+    }
+    let dest = line.parse::<i64>(parts[0], "a destination range start")?;
+    let src = line.parse::<i64>(parts[1], "a source range start")?;
+    let length = line.parse::<i64>(parts[2], "a range length")?;
+    match (src.checked_add(length), dest.checked_sub(src)) {
+        (Some(end), Some(addition)) => Ok(Rule {
+            range: src..end,
+            addition,
+        }),
+        _ => Err(line.error_line("expected ranges that fit in 64 bits")),
+    }
+}
+
+#[aoc_generator(day5)]
+fn parse_almanac(content: &str) -> Result<Almanac, ParseError> {
+    let mut lines = lines(5, content);
+    let seeds = parse_seeds(&next_line(5, content, &mut lines, "a seeds line")?)?;
+
+    let mut maps: Vec<Vec<Rule>> = Vec::new();
+    for line in lines {
+        if line.text.is_empty() {
+            continue;
+        }
+        if line.text.ends_with(" map:") {
+            maps.push(Vec::new());
+            continue;
         }
-        maps.push(mapping);
+        match maps.last_mut() {
+            Some(mapping) => mapping.push(parse_rule(&line)?),
+            None => return Err(line.error_line("expected a map header")),
+        }
+    }
+    if maps.is_empty() {
+        return Err(end_of_input(5, content, "expected a map"));
     }
-    maps
+    Ok(Almanac { seeds, maps })
 }
 
 #[aoc(day5, part1)]
-fn part1(content: &str) -> i64 {
-    let sections: Vec<&str> = content.split("\n\n").collect();
-
-    let seeds_strings: Vec<&str> = sections[0]
-        .split(": ")
-        .skip(1)
-        .map(|p| p.split_whitespace().collect::<Vec<&str>>())
-        .last()
-        .unwrap();
-    let seeds: Vec<i64> = seeds_strings
-        .iter()
-        .map(|p| p.parse::<i64>().unwrap())
-        .collect();
-
-    let maps: Vec<Vec<Rule>> = make_maps(sections[1..].to_vec());
-
+fn part1(almanac: &Almanac) -> i64 {
     let mut locations: Vec<i64> = Vec::new();
-    for seed in seeds {
+    for &seed in &almanac.seeds {
         // TODO functional
         let mut location = seed;
-        for mapping in &maps {
+        for mapping in &almanac.maps {
             for rule in mapping {
                 if rule.range.contains(&location) {
-                    location = location + rule.addition;
+                    location += rule.addition;
                     break;
                 }
             }
@@ -61,47 +91,60 @@ fn part1(content: &str) -> i64 {
     // 196167384
 }
 
-#[cfg(test)]
-mod tests {
-
-    use super::*;
-
-    const INPUT: &str = "seeds: 79 14 55 13
+pub struct Day05;
 
-seed-to-soil map:
-50 98 2
-52 50 48
+impl Solution for Day05 {
+    type Input = Almanac;
 
-soil-to-fertilizer map:
-0 15 37
-37 52 2
-39 0 15
+    const PARTS: u32 = 1;
 
-fertilizer-to-water map:
-49 53 8
-0 11 42
-42 0 7
-57 7 4
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_almanac(input)
+    }
 
-water-to-light map:
-88 18 7
-18 25 70
+    fn part1(input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(part1(input).into())
+    }
+}
 
-light-to-temperature map:
-45 77 23
-81 45 19
-68 64 13
+#[cfg(test)]
+mod tests {
 
-temperature-to-humidity map:
-0 69 1
-1 0 69
+    use proptest::prelude::*;
 
-humidity-to-location map:
-60 56 37
-56 93 4";
+    use super::*;
+    use crate::examples::{answer, example};
 
     #[test]
     fn test_part_1() {
-        assert_eq!(part1(&INPUT), 35);
+        let almanac = parse_almanac(&example(5, "part1")).unwrap();
+        assert_eq!(part1(&almanac).to_string(), answer(5, "part1"));
+    }
+
+    #[test]
+    fn test_truncated_almanac() {
+        let error = parse_almanac("seeds: 79 14 55 13\n\nseed-to-soil map:\n50 98").unwrap_err();
+        assert_eq!((error.line, error.column), (4, 1));
+        assert_eq!(parse_almanac("seeds: 79 14").unwrap_err().line, 2);
+        assert_eq!(parse_almanac("seeds:  ").unwrap_err().column, 8);
+    }
+
+    proptest! {
+        #[test]
+        fn test_shifting_maps(
+            seeds in prop::collection::vec(0..1000i64, 1..10),
+            shifts in prop::collection::vec(0..1000i64, 7),
+        ) {
+            let seeds_line = seeds.iter().map(|s| s.to_string()).collect::<Vec<_>>().join(" ");
+            // Every map moves all locations by its shift
+            let maps: String = shifts
+                .iter()
+                .enumerate()
+                .map(|(i, shift)| format!("\nmap{}-to-map{} map:\n{} 0 100000\n", i, i + 1, shift))
+                .collect();
+            let almanac = parse_almanac(&format!("seeds: {}\n{}", seeds_line, maps)).unwrap();
+            let expected = seeds.iter().min().unwrap() + shifts.iter().sum::<i64>();
+            prop_assert_eq!(part1(&almanac), expected);
+        }
     }
 }
@@ -1,26 +1,63 @@
-use aoc_runner_derive::aoc;
+use aoc_runner_derive::{aoc, aoc_generator};
 
-fn to_numbers(line: &str) -> Vec<u32> {
-    line.split(": ")
-        .skip(1)
-        .next()
-        .unwrap()
+use crate::answer::Answer;
+use crate::parse::{lines, next_line, Line, ParseError};
+use crate::solution::{Solution, SolveError};
+
+#[derive(Debug)]
+pub struct Races {
+    times: Vec<u32>,
+    records: Vec<u32>,
+    // The same sheet read without the spaces between the numbers
+    kerned_time: u64,
+    kerned_record: u64,
+}
+
+fn to_numbers(line: Line, prefix: &str) -> Result<Vec<u32>, ParseError> {
+    line.strip_prefix(line.text, prefix)?
         .split_whitespace()
-        .map(|s| s.parse::<u32>().unwrap())
+        .map(|s| line.parse::<u32>(s, "a number"))
         .collect()
 }
 
+fn to_kerned_number(line: Line, prefix: &str) -> Result<u64, ParseError> {
+    let value = line.strip_prefix(line.text, prefix)?;
+    value
+        .split_whitespace()
+        .collect::<String>()
+        .parse::<u64>()
+        .map_err(|_| line.error(value, "expected a number"))
+}
+
+#[aoc_generator(day6)]
+fn parse_races(content: &str) -> Result<Races, ParseError> {
+    let mut lines = lines(6, content);
+    let times_line = next_line(6, content, &mut lines, "a time line")?;
+    let records_line = next_line(6, content, &mut lines, "a distance line")?;
+
+    let times = to_numbers(times_line, "Time:")?;
+    let records = to_numbers(records_line, "Distance:")?;
+    if times.len() != records.len() {
+        return Err(records_line.error_line(format!("expected {} distances", times.len())));
+    }
+    Ok(Races {
+        times,
+        records,
+        kerned_time: to_kerned_number(times_line, "Time:")?,
+        kerned_record: to_kerned_number(records_line, "Distance:")?,
+    })
+}
+
 #[aoc(day6, part1)]
-fn part1(content: &str) -> u32 {
-    let parsed: Vec<Vec<u32>> = content.lines().map(to_numbers).collect();
-    let times = &parsed[0];
-    let records = &parsed[1];
+fn part1(races: &Races) -> u32 {
+    let times = &races.times;
+    let records = &races.records;
 
     (0..times.len())
         .map(|race_id| {
             (0..times[race_id])
-                .map(|pressed| 0u32.max(times[race_id] - pressed) * pressed)
-                .filter(|traveled| traveled > &records[race_id])
+                .map(|pressed| (times[race_id] - pressed) as u64 * pressed as u64)
+                .filter(|traveled| traveled > &(records[race_id] as u64))
                 .count() as u32
         })
         .product()
@@ -28,14 +65,9 @@ fn part1(content: &str) -> u32 {
 }
 
 #[aoc(day6, part2)]
-fn part2(content: &str) -> u64 {
-    let parsed: Vec<u64> = content
-        .replace(" ", "")
-        .lines()
-        .map(|l| l.split_once(':').unwrap().1.parse::<u64>().unwrap())
-        .collect();
-    let race_duration = parsed[0];
-    let record_distance = parsed[1];
+fn part2(races: &Races) -> u64 {
+    let race_duration = races.kerned_time;
+    let record_distance = races.kerned_record;
 
     for press_duration in 0..race_duration {
         let traveled = (race_duration - press_duration) * press_duration;
@@ -43,25 +75,72 @@ fn part2(content: &str) -> u64 {
             return race_duration - (2 * press_duration) + 1;
         }
     }
-    panic!("not found");
+    0
     // 28228952
 }
 
+pub struct Day06;
+
+impl Solution for Day06 {
+    type Input = Races;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_races(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(part1(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(part2(input).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
-    use super::*;
+    use proptest::prelude::*;
 
-    const INPUT: &str = "Time:      7  15   30
-Distance:  9  40  200";
+    use super::*;
+    use crate::examples::{answer, example};
 
     #[test]
     fn test_part_1() {
-        assert_eq!(part1(&INPUT), 288);
+        let input = parse_races(&example(6, "part1")).unwrap();
+        assert_eq!(part1(&input).to_string(), answer(6, "part1"));
     }
 
     #[test]
     fn test_part_2() {
-        assert_eq!(part2(&INPUT), 71503);
+        let input = parse_races(&example(6, "part1")).unwrap();
+        assert_eq!(part2(&input).to_string(), answer(6, "part2"));
+    }
+
+    #[test]
+    fn test_truncated_races() {
+        let error = parse_races("Time:      7  15   30").unwrap_err();
+        assert_eq!(
+            (error.line, error.reason.as_str()),
+            (2, "expected a distance line")
+        );
+        let error = parse_races("Time:      7  15   30\nDistance:  9  4O  200").unwrap_err();
+        assert_eq!(
+            (error.line, error.column, error.text.as_str()),
+            (2, 15, "4O")
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn test_single_race(time in 0..200u32, record in 0..10000u32) {
+            // Without spaces to remove, both parts read the same race
+            let races = parse_races(&format!("Time: {}\nDistance: {}", time, record)).unwrap();
+            let ways = (0..=time as u64)
+                .filter(|pressed| (time as u64 - pressed) * pressed > record as u64)
+                .count() as u64;
+            prop_assert_eq!(part1(&races) as u64, ways);
+            prop_assert_eq!(part2(&races), ways);
+        }
     }
 }
@@ -1,8 +1,12 @@
 use std::collections::HashMap;
 
-use aoc_runner_derive::aoc;
+use aoc_runner_derive::{aoc, aoc_generator};
 
-#[derive(PartialEq, Eq, PartialOrd, Ord)]
+use crate::answer::Answer;
+use crate::parse::{lines, Line, ParseError};
+use crate::solution::{Solution, SolveError};
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
 enum HandType {
     FiveOAK,
     FourOAK,
@@ -13,12 +17,40 @@ enum HandType {
     HighCard,
 }
 
+#[derive(Debug)]
+pub struct Bid {
+    cards: String,
+    bid: u32,
+}
+
 struct Hand {
     hand_type: HandType,
     strength: u32,
     bid: u32,
 }
 
+fn parse_bid(raw_line: Line) -> Result<Bid, ParseError> {
+    let line = raw_line.text.split_whitespace().collect::<Vec<&str>>();
+    let [cards, bid] = line[..] else {
+        return Err(raw_line.error_line("expected a hand and a bid"));
+    };
+    if let Some(invalid) = cards.find(|c: char| !"AKQJT98765432".contains(c)) {
+        return Err(raw_line.error(&cards[invalid..], "expected a card"));
+    }
+    if cards.len() != 5 {
+        return Err(raw_line.error(cards, "expected a hand of 5 cards"));
+    }
+    Ok(Bid {
+        cards: cards.to_string(),
+        bid: raw_line.parse::<u32>(bid, "a bid")?,
+    })
+}
+
+#[aoc_generator(day7)]
+fn parse_bids(content: &str) -> Result<Vec<Bid>, ParseError> {
+    lines(7, content).map(parse_bid).collect()
+}
+
 fn parse_hand_type(hand: &str, with_wildcard: bool) -> HandType {
     // Group chars and count occurrences, sort counts and map that to handtype
     // I thought this would be really elegant, the end result is a bit so-so
@@ -31,15 +63,15 @@ fn parse_hand_type(hand: &str, with_wildcard: bool) -> HandType {
     if with_wildcard && card_count_map.contains_key(&'J') && card_count_map.len() >= 2 {
         // Change the joker to the card with the most occurences
         let wildcard_count = card_count_map.remove(&'J').unwrap();
-        counts = card_count_map.values().map(|u| *u).collect::<Vec<u32>>();
-        counts.sort_by(|a, b| b.cmp(&a));
-        counts[0] = counts[0] + wildcard_count;
+        counts = card_count_map.values().copied().collect::<Vec<u32>>();
+        counts.sort_by(|a, b| b.cmp(a));
+        counts[0] += wildcard_count;
     } else {
-        counts = card_count_map.values().map(|u| *u).collect::<Vec<u32>>();
-        counts.sort_by(|a, b| b.cmp(&a));
+        counts = card_count_map.values().copied().collect::<Vec<u32>>();
+        counts.sort_by(|a, b| b.cmp(a));
     }
 
-    let hand_type = match counts[..] {
+    match counts[..] {
         [5] => HandType::FiveOAK,
         [4, 1] => HandType::FourOAK,
         [3, 2] => HandType::FullHouse,
@@ -47,19 +79,18 @@ fn parse_hand_type(hand: &str, with_wildcard: bool) -> HandType {
         [2, 2, 1] => HandType::TwoPair,
         [2, 1, 1, 1] => HandType::OnePair,
         [1, 1, 1, 1, 1] => HandType::HighCard,
-        _ => panic!("invalid card format or the parsing is broken"),
-    };
-    hand_type
+        _ => unreachable!("hands are validated to hold 5 cards when parsing"),
+    }
 }
 
-fn parse_line(raw_line: &str, with_wildcard: bool, joker_char: char) -> Hand {
-    let line = raw_line.split_whitespace().collect::<Vec<&str>>();
-    let hand_type = parse_hand_type(&line[0], with_wildcard);
+fn to_hand(bid: &Bid, with_wildcard: bool, joker_char: char) -> Hand {
+    let hand_type = parse_hand_type(&bid.cards, with_wildcard);
 
     // Convert str to hex (not using f (and 0 for pt1)) then to int to get the hand strength
     //  AKQJT987654321 -> edcba987654321 (without wildcard)
     //  AKQJT987654321 -> edc0a987654321 (with wildcard)
-    let hexadecimal: String = line[0]
+    let hexadecimal: String = bid
+        .cards
         .chars()
         .map(|c| match c {
             'A' => 'e',
@@ -74,17 +105,17 @@ fn parse_line(raw_line: &str, with_wildcard: bool, joker_char: char) -> Hand {
     Hand {
         hand_type,
         strength: u32::from_str_radix(&hexadecimal, 16).unwrap(),
-        bid: line[1].parse::<u32>().unwrap(),
+        bid: bid.bid,
     }
 }
 
-fn parse_line_pt1(raw_line: &str) -> Hand {
-    parse_line(raw_line, false, 'b')
+fn to_hand_pt1(bid: &Bid) -> Hand {
+    to_hand(bid, false, 'b')
 }
 
 #[aoc(day7, part1)]
-fn part1(content: &str) -> u32 {
-    let mut hands: Vec<Hand> = content.lines().map(parse_line_pt1).collect();
+fn part1(bids: &[Bid]) -> u32 {
+    let mut hands: Vec<Hand> = bids.iter().map(to_hand_pt1).collect();
 
     // Sort hands by Type (worst to best) then Strength (weak to strong)
     hands.sort_by(|a, b| {
@@ -101,13 +132,13 @@ fn part1(content: &str) -> u32 {
     // 253313241
 }
 
-fn parse_line_pt2(raw_line: &str) -> Hand {
-    parse_line(raw_line, true, '0')
+fn to_hand_pt2(bid: &Bid) -> Hand {
+    to_hand(bid, true, '0')
 }
 
 #[aoc(day7, part2)]
-fn part2(content: &str) -> u32 {
-    let mut hands: Vec<Hand> = content.lines().map(parse_line_pt2).collect();
+fn part2(bids: &[Bid]) -> u32 {
+    let mut hands: Vec<Hand> = bids.iter().map(to_hand_pt2).collect();
 
     // Sort hands by Type (worst to best) then Strength (weak to strong)
     hands.sort_by(|a, b| {
@@ -124,24 +155,79 @@ fn part2(content: &str) -> u32 {
     // 253362743
 }
 
+pub struct Day07;
+
+impl Solution for Day07 {
+    type Input = Vec<Bid>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_bids(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(part1(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(part2(input).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
-    use super::*;
+    use proptest::prelude::*;
 
-    const INPUT: &str = "32T3K 765
-T55J5 684
-KK677 28
-KTJJT 220
-QQQJA 483";
+    use super::*;
+    use crate::examples::{answer, example};
 
     #[test]
     fn test_part_1() {
-        assert_eq!(part1(&INPUT), 6440);
+        let input = parse_bids(&example(7, "part1")).unwrap();
+        assert_eq!(part1(&input).to_string(), answer(7, "part1"));
     }
 
     #[test]
     fn test_part_2() {
-        assert_eq!(part2(&INPUT), 5905);
+        let input = parse_bids(&example(7, "part1")).unwrap();
+        assert_eq!(part2(&input).to_string(), answer(7, "part2"));
+    }
+
+    #[test]
+    fn test_invalid_hand() {
+        let error = parse_bids("32T3K 765\nT55X5 684").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 4));
+        assert_eq!(parse_bids("32T3 765").unwrap_err().text, "32T3");
+        assert_eq!(parse_bids("32T3K").unwrap_err().column, 1);
+    }
+
+    const CARDS: &str = "AKQJT98765432";
+
+    /// A hand, the hand with its cards relabeled and the hand with its cards reordered
+    fn hand_and_permutations() -> impl Strategy<Value = (String, String, String)> {
+        let hand =
+            prop::collection::vec(prop::sample::select(CARDS.chars().collect::<Vec<_>>()), 5);
+        let labels = Just(CARDS.chars().collect::<Vec<_>>()).prop_shuffle();
+        (hand, labels).prop_flat_map(|(hand, labels)| {
+            let relabeled: String = hand
+                .iter()
+                .map(|&c| labels[CARDS.find(c).unwrap()])
+                .collect();
+            let reordered = Just(hand.clone())
+                .prop_shuffle()
+                .prop_map(String::from_iter);
+            (Just(String::from_iter(hand)), Just(relabeled), reordered)
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn test_hand_type_permutations((hand, relabeled, reordered) in hand_and_permutations()) {
+            prop_assert_eq!(parse_hand_type(&relabeled, false), parse_hand_type(&hand, false));
+            prop_assert_eq!(parse_hand_type(&reordered, false), parse_hand_type(&hand, false));
+            prop_assert_eq!(parse_hand_type(&reordered, true), parse_hand_type(&hand, true));
+            // Jokers only ever make a hand stronger
+            prop_assert!(parse_hand_type(&hand, true) <= parse_hand_type(&hand, false));
+        }
     }
 }
@@ -1,20 +1,60 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 
-use aoc_runner_derive::aoc;
+use aoc_runner_derive::{aoc, aoc_generator};
+
+use crate::answer::Answer;
+use crate::parse::{lines, next_line, Line, ParseError};
+use crate::solution::{Solution, SolveError};
+
+type Network = HashMap<String, (String, String)>;
+
+fn parse_rule<'a>(rule: &Line<'a>) -> Result<(&'a str, &'a str, &'a str), ParseError> {
+    let (from, left_right) = rule.split_once(rule.text, "=")?;
+    let left_right = rule.strip_suffix(left_right.trim(), ")")?;
+    let left_right = rule.strip_prefix(left_right, "(")?;
+    let (left, right) = rule.split_once(left_right, ",")?;
+    Ok((from.trim(), left.trim(), right.trim()))
+}
+
+#[aoc_generator(day8)]
+fn parse_network(content: &str) -> Result<(String, Network), ParseError> {
+    let mut lines = lines(8, content);
+    let instructions = next_line(8, content, &mut lines, "a line of instructions")?;
+    if let Some(invalid) = instructions.text.find(|c| c != 'L' && c != 'R') {
+        return Err(instructions.error(&instructions.text[invalid..], "expected L or R"));
+    }
+    if instructions.text.is_empty() {
+        return Err(instructions.error_line("expected at least one instruction"));
+    }
+    let separator = next_line(8, content, &mut lines, "an empty line")?;
+    if !separator.text.is_empty() {
+        return Err(separator.error_line("expected an empty line"));
+    }
+
+    let mut rules: Vec<(Line, (&str, &str, &str))> = Vec::new();
+    for rule in lines {
+        rules.push((rule, parse_rule(&rule)?));
+    }
+    let mapping: Network = rules
+        .iter()
+        .map(|(_, (from, left, right))| (from.to_string(), (left.to_string(), right.to_string())))
+        .collect();
+
+    // Every node that can be reached must be defined, otherwise walking the network fails halfway
+    for (rule, (_, left, right)) in rules.iter() {
+        for node in [left, right] {
+            if !mapping.contains_key(*node) {
+                return Err(rule.error(node, format!("undefined node {}", node)));
+            }
+        }
+    }
+    Ok((instructions.text.to_string(), mapping))
+}
 
 #[aoc(day8, part1)]
-fn part1(content: &str) -> u32 {
-    let instructions = content.lines().next().unwrap();
-    let rules: Vec<&str> = content.lines().skip(2).collect();
-    let mut mapping: HashMap<String, (String, String)> = HashMap::new();
-    for rule in rules.iter() {
-        let split: String = rule.replace(" ", "");
-        let (from, left_right) = split.split_once('=').unwrap();
-        let (left, right) = left_right.split_once(',').unwrap();
-        mapping.insert(
-            from.to_string(),
-            (left[1..].to_string(), right[..right.len() - 1].to_string()),
-        );
+fn part1((instructions, mapping): &(String, Network)) -> Option<u32> {
+    if !mapping.contains_key("AAA") || !mapping.contains_key("ZZZ") {
+        return None;
     }
 
     let mut position = String::from("AAA");
@@ -29,51 +69,31 @@ fn part1(content: &str) -> u32 {
             };
             step += 1;
             if position == "ZZZ" {
-                return step;
+                return Some(step);
             }
         }
     }
     //19667
 }
 
-fn prime_factors(number: &u128) -> Vec<u128> {
-    let mut i = 2;
-    let mut factors: Vec<u128> = Vec::new();
-
-    let mut n = *number;
-    while i * i <= n {
-        if (n % i) > 0 {
-            i += 1;
-        } else {
-            n = n / i;
-            factors.push(i);
-        }
-    }
-    if n > 1 {
-        factors.push(n);
+fn gcd(a: u128, b: u128) -> u128 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
     }
-    factors
 }
 
 #[aoc(day8, part2)]
-fn part2(content: &str) -> u128 {
-    let instructions = content.lines().next().unwrap();
-    let rules: Vec<&str> = content.lines().skip(2).collect();
-    let mut mapping: HashMap<String, (String, String)> = HashMap::new();
-
-    let mut heads: Vec<String> = Vec::new();
-    for rule in rules.iter() {
-        let split: String = rule.replace(" ", "");
-        let (from, left_right) = split.split_once('=').unwrap();
-        let (left, right) = left_right.split_once(',').unwrap();
-
-        if from.ends_with("A") {
-            heads.push(from.to_string());
-        }
-        mapping.insert(
-            from.to_string(),
-            (left[1..].to_string(), right[..right.len() - 1].to_string()),
-        );
+fn part2((instructions, mapping): &(String, Network)) -> Option<u128> {
+    let mut heads: Vec<String> = mapping
+        .keys()
+        .filter(|from| from.ends_with('A'))
+        .cloned()
+        .collect();
+    heads.sort();
+    if heads.is_empty() {
+        return None;
     }
 
     let mut step = 0u128;
@@ -88,21 +108,19 @@ fn part2(content: &str) -> u128 {
                 } else {
                     right.to_string()
                 };
-                if position.ends_with("Z") && !first_z_per_head.contains_key(&position) {
+                if position.ends_with('Z') && !first_z_per_head.contains_key(&position) {
                     // Keep track of the first Z position per head
                     // The number of steps between a Z position for each head is constant
                     first_z_per_head.insert(position.to_string(), step);
 
                     // When we know the first_z for each head, we can calculate the step at which all heads will
-                    // align on a Z position. This is the product of the prime factors of each number
+                    // align on a Z position. This is the least common multiple of the first_z of each head
                     if first_z_per_head.len() == heads.len() {
-                        let mut unique_factors: HashSet<u128> = HashSet::new();
-                        for first_z in first_z_per_head.values() {
-                            for prime_factor in prime_factors(first_z) {
-                                unique_factors.insert(prime_factor);
-                            }
-                        }
-                        return unique_factors.iter().product();
+                        return Some(
+                            first_z_per_head
+                                .values()
+                                .fold(1, |lcm, &first_z| lcm / gcd(lcm, first_z) * first_z),
+                        );
                     }
                 }
                 heads[idx] = position;
@@ -112,50 +130,120 @@ fn part2(content: &str) -> u128 {
     // 19185263738117
 }
 
-#[cfg(test)]
-mod tests {
+pub struct Day08;
 
-    use super::*;
+impl Solution for Day08 {
+    type Input = (String, Network);
 
-    const INPUT1: &str = "LLR
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_network(input)
+    }
 
-AAA = (BBB, BBB)
-BBB = (AAA, ZZZ)
-ZZZ = (ZZZ, ZZZ)";
+    fn part1(input: &Self::Input) -> Result<Answer, SolveError> {
+        part1(input)
+            .map(Answer::from)
+            .ok_or(SolveError::NoAnswer("the network has no AAA or ZZZ node"))
+    }
 
-    const INPUT2: &str = "RL
+    fn part2(input: &Self::Input) -> Result<Answer, SolveError> {
+        part2(input).map(Answer::from).ok_or(SolveError::NoAnswer(
+            "the network has no nodes ending with A",
+        ))
+    }
+}
 
-AAA = (BBB, CCC)
-BBB = (DDD, EEE)
-CCC = (ZZZ, GGG)
-DDD = (DDD, DDD)
-EEE = (EEE, EEE)
-GGG = (GGG, GGG)
-ZZZ = (ZZZ, ZZZ)";
+#[cfg(test)]
+mod tests {
 
-    const INPUT3: &str = "LR
+    use proptest::prelude::*;
 
-11A = (11B, XXX)
-11B = (XXX, 11Z)
-11Z = (11B, XXX)
-22A = (22B, XXX)
-22B = (22C, 22C)
-22C = (22Z, 22Z)
-22Z = (22B, 22B)
-XXX = (XXX, XXX)";
+    use super::*;
+    use crate::examples::{answer, example};
 
     #[test]
-    fn test_part_1_input1() {
-        assert_eq!(part1(&INPUT1), 6);
+    fn test_part_1() {
+        for name in ["part1", "part1-2"] {
+            let network = parse_network(&example(8, name)).unwrap();
+            assert_eq!(part1(&network).unwrap().to_string(), answer(8, name));
+        }
     }
 
     #[test]
-    fn test_part_1_input2() {
-        assert_eq!(part1(&INPUT2), 2);
+    fn test_part_2() {
+        let network = parse_network(&example(8, "part2")).unwrap();
+        assert_eq!(part2(&network).unwrap().to_string(), answer(8, "part2"));
+        assert_eq!(part1(&network), None);
     }
 
     #[test]
-    fn test_part_2() {
-        assert_eq!(part2(&INPUT3), 6);
+    fn test_invalid_network() {
+        let error = parse_network("LLR\n\nAAA = (BBB, BBB)\nBBB = (AAA, ZZZ").unwrap_err();
+        assert_eq!((error.line, error.column), (4, 7));
+        let error = parse_network("LLR\n\nAAA = (BBB, BBB)\nBBB = (AAA, ZZZ)").unwrap_err();
+        assert_eq!(
+            (error.line, error.column, error.text.as_str()),
+            (4, 13, "ZZZ")
+        );
+        assert_eq!(parse_network("LXR").unwrap_err().column, 2);
+    }
+
+    #[test]
+    fn test_shared_prime_factors() {
+        // Cycles of 4 and 2 steps align after 4 steps, not after the product of their primes
+        let network = parse_network(
+            "LR\n\n\
+             11A = (11B, 11B)\n11B = (11C, 11C)\n11C = (11D, 11D)\n11D = (11Z, 11Z)\n11Z = (11B, 11B)\n\
+             22A = (22B, 22B)\n22B = (22Z, 22Z)\n22Z = (22B, 22B)",
+        )
+        .unwrap();
+        assert_eq!(part2(&network), Some(4));
+    }
+
+    proptest! {
+        #[test]
+        fn test_chain(length in 1..20usize, instructions in "[LR]{0,8}L[LR]{0,8}") {
+            // Left moves along the chain from AAA to ZZZ, right stays in place
+            let mut nodes: Vec<String> = (1..length).map(|i| format!("N{}", i)).collect();
+            nodes.insert(0, "AAA".to_string());
+            nodes.push("ZZZ".to_string());
+            let rules: Vec<String> = nodes
+                .iter()
+                .enumerate()
+                .map(|(i, node)| {
+                    let next = &nodes[(i + 1).min(length)];
+                    format!("{} = ({}, {})", node, next, node)
+                })
+                .collect();
+            let network = parse_network(&format!("{}\n\n{}", instructions, rules.join("\n"))).unwrap();
+
+            let expected = instructions
+                .chars()
+                .cycle()
+                .enumerate()
+                .filter(|(_, c)| *c == 'L')
+                .nth(length - 1)
+                .unwrap()
+                .0 as u32
+                + 1;
+            prop_assert_eq!(part1(&network), Some(expected));
+        }
+
+        #[test]
+        fn test_ghost_cycles(lengths in prop::collection::vec(1..30u128, 1..5)) {
+            // Every ghost loops through its own cycle, reaching its Z node every `length` steps
+            let mut rules = Vec::new();
+            for (ghost, &length) in lengths.iter().enumerate() {
+                let mut nodes: Vec<String> = (1..length).map(|i| format!("G{}N{}", ghost, i)).collect();
+                nodes.push(format!("G{}Z", ghost));
+                rules.push(format!("G{}A = ({}, {})", ghost, nodes[0], nodes[0]));
+                for (i, node) in nodes.iter().enumerate() {
+                    let next = &nodes[(i + 1) % nodes.len()];
+                    rules.push(format!("{} = ({}, {})", node, next, next));
+                }
+            }
+            let network = parse_network(&format!("LR\n\n{}", rules.join("\n"))).unwrap();
+            let lcm = lengths.iter().fold(1, |lcm, &length| lcm / gcd(lcm, length) * length);
+            prop_assert_eq!(part2(&network), Some(lcm));
+        }
     }
 }
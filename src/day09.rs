@@ -1,9 +1,13 @@
-use aoc_runner_derive::aoc;
+use aoc_runner_derive::{aoc, aoc_generator};
 
-fn next_value(values_: Vec<i64>) -> i64 {
+use crate::answer::Answer;
+use crate::parse::{lines, Line, ParseError};
+use crate::solution::{Solution, SolveError};
+
+fn next_value(values_: &[i64]) -> i64 {
     // Given a vector of integers following a certain trend, calculate the next integer
     let mut last_values: Vec<i64> = vec![];
-    let mut values: Vec<i64> = values_.clone();
+    let mut values: Vec<i64> = values_.to_vec();
     loop {
         if values.iter().all(|v| v == &0) {
             return last_values.iter().sum();
@@ -15,68 +19,133 @@ fn next_value(values_: Vec<i64>) -> i64 {
     }
 }
 
-fn next_history_value(line: &str) -> i64 {
-    next_value(
-        line.split_whitespace()
-            .map(|s| s.parse::<i64>().unwrap())
-            .collect(),
-    )
+fn parse_history(line: Line) -> Result<Vec<i64>, ParseError> {
+    line.text
+        .split_whitespace()
+        .map(|s| line.parse::<i64>(s, "a number"))
+        .collect()
+}
+
+#[aoc_generator(day9)]
+fn parse_histories(content: &str) -> Result<Vec<Vec<i64>>, ParseError> {
+    lines(9, content).map(parse_history).collect()
 }
 
 #[aoc(day9, part1)]
-fn part1(content: &str) -> i64 {
-    content.lines().map(next_history_value).sum()
+fn part1(histories: &[Vec<i64>]) -> i64 {
+    histories.iter().map(|h| next_value(h)).sum()
     // 1819125966
 }
 
-fn next_backwards_history_value(line: &str) -> i64 {
-    next_value(
-        line.split_whitespace()
-            .map(|s| s.parse::<i64>().unwrap())
-            .rev()
-            .collect(),
-    )
+fn next_backwards_history_value(history: &[i64]) -> i64 {
+    next_value(&history.iter().rev().copied().collect::<Vec<i64>>())
 }
 
 #[aoc(day9, part2)]
-fn part2(content: &str) -> i64 {
-    content.lines().map(next_backwards_history_value).sum()
+fn part2(histories: &[Vec<i64>]) -> i64 {
+    histories
+        .iter()
+        .map(|h| next_backwards_history_value(h))
+        .sum()
     // 1140
 }
 
+pub struct Day09;
+
+impl Solution for Day09 {
+    type Input = Vec<Vec<i64>>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_histories(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(part1(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(part2(input).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
-    use super::*;
+    use proptest::prelude::*;
 
-    const INPUT: &str = "0 3 6 9 12 15
-1 3 6 10 15 21
-10 13 16 21 30 45";
+    use super::*;
+    use crate::examples::{answer, example};
 
     #[test]
     fn test_part_1() {
-        assert_eq!(part1("19 31 40 46 49 49"), 46);
-        assert_eq!(part1("0 0"), 0);
-        assert_eq!(part1("-1 -1"), -1);
-        assert_eq!(part1("9 6 2 -3"), -9);
-        assert_eq!(part1("-159 -178 -198 -219"), -241);
-        assert_eq!(part1("12 21 35 61 126 285 637 1370 2868 5929 12171 24755 49646 97785 188781 357080 662060 1204173 2150147 3771417"), 6501422);
-        assert_eq!(part1(&INPUT), 114);
+        assert_eq!(part1(&parse_histories("19 31 40 46 49 49").unwrap()), 46);
+        assert_eq!(part1(&parse_histories("0 0").unwrap()), 0);
+        assert_eq!(part1(&parse_histories("-1 -1").unwrap()), -1);
+        assert_eq!(part1(&parse_histories("9 6 2 -3").unwrap()), -9);
+        assert_eq!(
+            part1(&parse_histories("-159 -178 -198 -219").unwrap()),
+            -241
+        );
+        assert_eq!(part1(&parse_histories("12 21 35 61 126 285 637 1370 2868 5929 12171 24755 49646 97785 188781 357080 662060 1204173 2150147 3771417").unwrap()), 6501422);
+        let histories = parse_histories(&example(9, "part1")).unwrap();
+        assert_eq!(part1(&histories).to_string(), answer(9, "part1"));
     }
 
     #[test]
     fn test_part_2() {
-        assert_eq!(-5, part2("6 53 182 458 954 1719 2709 3660 3877 1908 -4933"));
-        assert_eq!(19, part2("31 40 46 49 49 46"));
-        assert_eq!(-159, part2("-178 -198 -219 -241"),);
-        assert_eq!(2, part2(&INPUT));
+        assert_eq!(
+            -5,
+            part2(&parse_histories("6 53 182 458 954 1719 2709 3660 3877 1908 -4933").unwrap())
+        );
+        assert_eq!(19, part2(&parse_histories("31 40 46 49 49 46").unwrap()));
+        assert_eq!(
+            -159,
+            part2(&parse_histories("-178 -198 -219 -241").unwrap())
+        );
+        let histories = parse_histories(&example(9, "part1")).unwrap();
+        assert_eq!(part2(&histories).to_string(), answer(9, "part2"));
         assert_eq!(
             1,
-            part2("1 1 5 13 25 51 128 353 963 2541 6528 16414 40328 96357 222999 499077 1080929")
+            part2(
+                &parse_histories(
+                    "1 1 5 13 25 51 128 353 963 2541 6528 16414 40328 96357 222999 499077 1080929"
+                )
+                .unwrap()
+            )
         );
         assert_eq!(
             12,
-            part2("12 16 26 51 103 202 405 870 1965 4434 9640 19935 39296 74578 138165 253576")
+            part2(
+                &parse_histories(
+                    "12 16 26 51 103 202 405 870 1965 4434 9640 19935 39296 74578 138165 253576"
+                )
+                .unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn test_invalid_history() {
+        let error = parse_histories("0 3 6\n1 3 6.5 10").unwrap_err();
+        assert_eq!(
+            (error.line, error.column, error.text.as_str()),
+            (2, 5, "6.5")
         );
     }
+
+    proptest! {
+        #[test]
+        fn test_polynomials(
+            coefficients in prop::collection::vec(-10..=10i64, 1..5),
+            extra in 1..5usize,
+        ) {
+            // Polynomials of degree d are extrapolated exactly from d + 2 values
+            let length = coefficients.len() + extra;
+            let p = |x: i64| coefficients.iter().rev().fold(0, |acc, c| acc * x + c);
+            let history: Vec<String> = (0..length as i64).map(|x| p(x).to_string()).collect();
+            let histories = parse_histories(&history.join(" ")).unwrap();
+            prop_assert_eq!(part1(&histories), p(length as i64));
+            prop_assert_eq!(part2(&histories), p(-1));
+        }
+    }
 }
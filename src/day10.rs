@@ -1,41 +1,16 @@
 use std::collections::{HashMap, HashSet};
 
-use aoc_runner_derive::aoc;
+use aoc_runner_derive::{aoc, aoc_generator};
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
-struct Position {
-    x: i32,
-    y: i32,
-}
+use crate::answer::Answer;
+use crate::geometry::{shoelace_doubled_signed, Direction, Point};
+use crate::parse::{end_of_input, lines, Line, ParseError};
+use crate::solution::{Solution, SolveError};
+use crate::svg::{Style as SvgStyle, Svg};
 
-impl Position {
-    fn north(&self) -> Position {
-        Position {
-            x: self.x,
-            y: self.y - 1,
-        }
-    }
-    fn east(&self) -> Position {
-        Position {
-            x: self.x + 1,
-            y: self.y,
-        }
-    }
-    fn south(&self) -> Position {
-        Position {
-            x: self.x,
-            y: self.y + 1,
-        }
-    }
-    fn west(&self) -> Position {
-        Position {
-            x: self.x - 1,
-            y: self.y,
-        }
-    }
-}
+type Position = Point<i32>;
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Debug)]
 enum Shape {
     Horizontal, // -
     Vertical,   // |
@@ -46,35 +21,70 @@ enum Shape {
 }
 
 impl Shape {
-    fn connectors(&self, position: &Position) -> (Position, Position) {
-        /* Given a position, return the a tuple of 2 positions to the pipe's connectors  */
+    fn directions(&self) -> (Direction, Direction) {
+        /* Return the directions in which the pipe's connectors point */
         match self {
-            Shape::Horizontal => (position.west(), position.east()),
-            Shape::Vertical => (position.north(), position.south()),
-            Shape::SouthEast => (position.south(), position.east()),
-            Shape::SouthWest => (position.south(), position.west()),
-            Shape::NorthEast => (position.north(), position.east()),
-            Shape::NorthWest => (position.north(), position.west()),
+            Shape::Horizontal => (Direction::West, Direction::East),
+            Shape::Vertical => (Direction::North, Direction::South),
+            Shape::SouthEast => (Direction::South, Direction::East),
+            Shape::SouthWest => (Direction::South, Direction::West),
+            Shape::NorthEast => (Direction::North, Direction::East),
+            Shape::NorthWest => (Direction::North, Direction::West),
         }
     }
+
+    fn box_drawing(&self) -> char {
+        match self {
+            Shape::Horizontal => '─',
+            Shape::Vertical => '│',
+            Shape::NorthEast => '└',
+            Shape::NorthWest => '┘',
+            Shape::SouthEast => '┌',
+            Shape::SouthWest => '┐',
+        }
+    }
+
+    fn connectors(&self, position: &Position) -> (Position, Position) {
+        /* Given a position, return the a tuple of 2 positions to the pipe's connectors  */
+        let (direction1, direction2) = self.directions();
+        (position.step(direction1), position.step(direction2))
+    }
 }
 
+#[derive(Debug)]
 enum Tile {
     Ground,
     Pipe { pos: Position, shape: Shape },
 }
 
-fn create_grid(content: &str) -> (HashMap<Position, Tile>, Position, Position) {
+#[derive(Debug)]
+pub struct Maze {
+    grid: HashMap<Position, Tile>,
+    main_loop: HashSet<Position>,
+    animal: Position,
+    width: i32,
+    height: i32,
+}
+
+fn tile_error(content: &str, pos: &Position, reason: &str) -> ParseError {
+    let line: Line = lines(10, content).nth(pos.y as usize).unwrap();
+    let x = pos.x as usize;
+    line.error(&line.text[x..x + 1], reason)
+}
+
+fn create_grid(content: &str) -> Result<(HashMap<Position, Tile>, Position), ParseError> {
     // Create grid of positions mapped to tiles (pipes/ground)
     // Track the animal's location
     let mut grid: HashMap<Position, Tile> = HashMap::new();
     let mut _animal: Option<Position> = None;
-    for (y, line) in content.lines().enumerate() {
-        for (x, c) in line.chars().enumerate() {
-            let pos = Position {
-                x: x as i32,
-                y: y as i32,
-            };
+    let width = content.lines().next().map_or(0, |l| l.len());
+    for line in lines(10, content) {
+        let y = line.number - 1;
+        if line.text.len() != width {
+            return Err(line.error_line(format!("expected {} tiles", width)));
+        }
+        for (x, c) in line.text.char_indices() {
+            let pos = Position::new(x as i32, y as i32);
             let tile_shape = match c {
                 '|' => Some(Shape::Vertical),
                 '-' => Some(Shape::Horizontal),
@@ -82,117 +92,175 @@ fn create_grid(content: &str) -> (HashMap<Position, Tile>, Position, Position) {
                 'J' => Some(Shape::NorthWest),
                 'F' => Some(Shape::SouthEast),
                 '7' => Some(Shape::SouthWest),
+                'S' if _animal.is_some() => {
+                    return Err(line.error(&line.text[x..], "expected a single animal"))
+                }
                 'S' => {
-                    _animal = Some(pos.clone()); // record the position, figure out the shape later
+                    _animal = Some(pos); // record the position, figure out the shape later
                     None
                 }
-                _ => None,
+                '.' => None,
+                _ => return Err(line.error(&line.text[x..], "expected a pipe, '.' or 'S'")),
             };
             let tile = match tile_shape {
-                Some(shape) => Tile::Pipe {
-                    pos: pos.clone(),
-                    shape,
-                },
+                Some(shape) => Tile::Pipe { pos, shape },
                 None => Tile::Ground,
             };
             grid.insert(pos, tile);
         }
     }
 
-    // Derive shape of pipe where the animal resides.
-    // Would not have been necessary with a sane input format...
-    // Assume exactly 2 pipes connect to the animal's current tile - in my input this is the case
-    let animal: Position = _animal.unwrap();
-    let direction_matches: Vec<bool> =
-        vec![animal.north(), animal.east(), animal.south(), animal.west()]
-            .iter()
-            .map(|direction| match grid.get(direction) {
-                None => false,
-                Some(tile) => match tile {
-                    Tile::Pipe { pos: p, shape: s } => {
-                        let (connector1, connector2) = s.connectors(&p);
-                        connector1 == animal || connector2 == animal
-                    }
-                    _ => false,
-                },
-            })
-            .collect();
-
-    let animal_pipe_shape = match direction_matches[..] {
-        // North East South West
-        [false, true, false, true] => Shape::Horizontal,
-        [true, false, true, false] => Shape::Vertical,
-        [false, true, true, false] => Shape::SouthEast,
-        [false, false, true, true] => Shape::SouthWest,
-        [true, true, false, false] => Shape::NorthEast,
-        [true, false, false, true] => Shape::NorthWest,
-        _ => panic!("invalid direction matches for animal tile"),
-    };
-
-    // Add pipe on animal's tile to the grid and select one of its connectors as next position
-    let animal_tile = Tile::Pipe {
-        pos: animal.clone(),
-        shape: animal_pipe_shape.clone(),
-    };
-    grid.insert(animal.clone(), animal_tile);
-    let (next_position, _) = animal_pipe_shape.connectors(&animal);
-
-    (grid, animal, next_position)
+    let animal: Position =
+        _animal.ok_or_else(|| end_of_input(10, content, "expected an animal 'S'"))?;
+    Ok((grid, animal))
 }
 
+const SHAPES: [Shape; 6] = [
+    Shape::Horizontal,
+    Shape::Vertical,
+    Shape::NorthEast,
+    Shape::NorthWest,
+    Shape::SouthEast,
+    Shape::SouthWest,
+];
+
 fn discover_main_loop(
+    content: &str,
     grid: &HashMap<Position, Tile>,
     animal: &Position,
-    next_position: Position,
-) -> HashSet<Position> {
+) -> Result<HashSet<Position>, ParseError> {
     // From the animal's current tile, traverse all pipe connectors until we're back at the start
-    let mut cur_pos: Position = next_position.clone();
-    let mut prev_pos: Position = animal.clone();
+    let (next_position, last_position) = match grid.get(animal) {
+        Some(Tile::Pipe { pos, shape }) => shape.connectors(pos),
+        _ => unreachable!("the animal's pipe is inferred before discovering the loop"),
+    };
+    let mut cur_pos: Position = next_position;
+    let mut prev_pos: Position = *animal;
     let mut main_loop: HashSet<Position> = HashSet::new();
     while cur_pos != *animal {
-        main_loop.insert(prev_pos.clone());
-        main_loop.insert(cur_pos.clone());
-        let tile = (*grid).get(&cur_pos).unwrap();
-        let (conn1, conn2) = match tile {
-            Tile::Pipe {
+        main_loop.insert(prev_pos);
+        main_loop.insert(cur_pos);
+        let (conn1, conn2) = match grid.get(&cur_pos) {
+            Some(Tile::Pipe {
                 pos: next_pos,
                 shape: next_shape,
-            } => next_shape.connectors(next_pos),
-            Tile::Ground => panic!("there's not supposed to be ground here"),
+            }) => next_shape.connectors(next_pos),
+            _ => return Err(tile_error(content, &prev_pos, "pipe leads out of the loop")),
         };
+        if conn1 != prev_pos && conn2 != prev_pos {
+            return Err(tile_error(content, &prev_pos, "pipe leads out of the loop"));
+        }
         let next_pos: Position = if conn1 != prev_pos { conn1 } else { conn2 };
         prev_pos = cur_pos;
         cur_pos = next_pos;
     }
-    main_loop.clone()
+    // The loop has to come back through the other end of the animal's pipe
+    if prev_pos != last_position {
+        return Err(tile_error(
+            content,
+            animal,
+            "expected the loop to close through the animal's pipe",
+        ));
+    }
+    Ok(main_loop)
 }
 
-#[aoc(day10, part1)]
-fn part1(content: &str) -> i32 {
-    let (grid, animal, next_position): (HashMap<Position, Tile>, Position, Position) =
-        create_grid(content);
+/// Find the shape of the animal's pipe, the only one that closes a loop through the animal
+///
+/// Every shape connecting two of the neighbouring pipes that point at the animal is a candidate,
+/// the loops of the candidates are tried one by one.
+fn infer_animal(
+    content: &str,
+    grid: &mut HashMap<Position, Tile>,
+    animal: Position,
+) -> Result<HashSet<Position>, ParseError> {
+    let points_at_animal = |pos: &Position, grid: &HashMap<Position, Tile>| match grid.get(pos) {
+        Some(Tile::Pipe { pos: p, shape: s }) => {
+            let (connector1, connector2) = s.connectors(p);
+            connector1 == animal || connector2 == animal
+        }
+        _ => false,
+    };
+    let candidates: Vec<Shape> = SHAPES
+        .into_iter()
+        .filter(|shape| {
+            let (connector1, connector2) = shape.connectors(&animal);
+            points_at_animal(&connector1, grid) && points_at_animal(&connector2, grid)
+        })
+        .collect();
 
-    let main_loop = discover_main_loop(&grid, &animal, next_position);
+    let mut loops: Vec<(Shape, Result<HashSet<Position>, ParseError>)> = candidates
+        .into_iter()
+        .map(|shape| {
+            grid.insert(
+                animal,
+                Tile::Pipe {
+                    pos: animal,
+                    shape: shape.clone(),
+                },
+            );
+            let main_loop = discover_main_loop(content, grid, &animal);
+            (shape, main_loop)
+        })
+        .collect();
 
-    main_loop.len() as i32 / 2
-    // 6768
+    // A single candidate keeps the error of its loop, which points at where the loop breaks
+    if loops.len() == 1 {
+        let (_, main_loop) = loops.pop().unwrap();
+        return main_loop;
+    }
+    loops.retain(|(_, main_loop)| main_loop.is_ok());
+    match loops.len() {
+        1 => {
+            let (shape, main_loop) = loops.pop().unwrap();
+            grid.insert(animal, Tile::Pipe { pos: animal, shape });
+            main_loop
+        }
+        0 => Err(tile_error(
+            content,
+            &animal,
+            "expected at least 2 pipes connecting to the animal in a loop",
+        )),
+        n => Err(tile_error(
+            content,
+            &animal,
+            &format!("expected a single loop through the animal, found {}", n),
+        )),
+    }
 }
 
-#[aoc(day10, part2)]
-fn part2(content: &str) -> i32 {
-    let (grid, animal, next_position): (HashMap<Position, Tile>, Position, Position) =
-        create_grid(content);
+#[aoc_generator(day10)]
+fn parse_maze(content: &str) -> Result<Maze, ParseError> {
+    let (mut grid, animal): (HashMap<Position, Tile>, Position) = create_grid(content)?;
 
-    let main_loop = discover_main_loop(&grid, &animal, next_position);
+    let main_loop = infer_animal(content, &mut grid, animal)?;
 
-    let width = content
-        .lines()
-        .map(|l| l.len())
-        .take(1)
-        .collect::<Vec<usize>>()[0] as i32;
-    let height = content.lines().count() as i32;
+    Ok(Maze {
+        grid,
+        main_loop,
+        animal,
+        width: content.lines().next().map_or(0, |l| l.len()) as i32,
+        height: content.lines().count() as i32,
+    })
+}
+
+#[aoc(day10, part1)]
+fn part1(maze: &Maze) -> i32 {
+    maze.main_loop.len() as i32 / 2
+    // 6768
+}
 
-    let mut count_enclosed = 0;
+/// Tiles enclosed by the main loop, found by scanning the grid line by line
+fn enclosed(maze: &Maze) -> HashSet<Position> {
+    let Maze {
+        grid,
+        main_loop,
+        width,
+        height,
+        ..
+    } = maze;
+
+    let mut enclosed = HashSet::new();
 
     // Traverse the grid line by line. When crossing a vertical pipe that is part of the main loop, every
     // subsequent ground or junk-pipe is enclosed by the loop. When encountering another vertical tile,
@@ -200,21 +268,26 @@ fn part2(content: &str) -> i32 {
     // pipes like L7 and FJ (optionally including horizontal pipes) which function as a vertical pipe.
     // But pipes like LJ and F7 are 'U-turns' which don't change the state of the loop.
     // Credits to @MPinna for the idea!
-    for y in 0..height {
+    for y in 0..*height {
         let mut currently_enclosed = false;
         let mut last_curve_shape: Option<Shape> = None;
-        for x in 0..width {
-            let pos = Position { x, y };
+        for x in 0..*width {
+            let pos = Position::new(x, y);
             let tile = grid.get(&pos).unwrap();
             match tile {
-                Tile::Ground => count_enclosed += currently_enclosed as i32,
+                Tile::Ground if currently_enclosed => {
+                    enclosed.insert(pos);
+                }
+                Tile::Ground => (),
                 Tile::Pipe {
                     pos: _,
                     shape: current_shape,
                 } => {
                     if !main_loop.contains(&pos) {
                         // junk pipe
-                        count_enclosed += currently_enclosed as i32;
+                        if currently_enclosed {
+                            enclosed.insert(pos);
+                        }
                         continue;
                     }
                     if current_shape == &Shape::Horizontal {
@@ -238,11 +311,10 @@ fn part2(content: &str) -> i32 {
                         }
                     };
 
-                    if match (&last_curve, current_shape) {
-                        (Shape::NorthEast, Shape::SouthWest) => true,
-                        (Shape::SouthEast, Shape::NorthWest) => true,
-                        _ => false,
-                    } {
+                    if matches!(
+                        (&last_curve, current_shape),
+                        (Shape::NorthEast, Shape::SouthWest) | (Shape::SouthEast, Shape::NorthWest)
+                    ) {
                         // L7, L---7, FJ, F---J, etc are zigzags that function the same as a |, flip the enclosure
                         last_curve_shape = None;
                         currently_enclosed = !currently_enclosed;
@@ -254,97 +326,407 @@ fn part2(content: &str) -> i32 {
             }
         }
     }
-    count_enclosed
+    enclosed
+}
+
+/// Tiles enclosed by the main loop, found by flood filling the outside on a grid of twice the
+/// resolution, where the gaps between adjacent pipes are tiles of their own
+///
+/// Slower than [`enclosed`], only used to cross-check it.
+#[cfg(test)]
+fn enclosed_by_flood_fill(maze: &Maze) -> HashSet<Position> {
+    let Maze {
+        grid,
+        main_loop,
+        width,
+        height,
+        ..
+    } = maze;
+
+    // Tile (x, y) becomes (2x, 2y), a pipe of the loop also walls off the half tiles it connects to
+    let mut walls: HashSet<Position> = HashSet::new();
+    for pos in main_loop {
+        let doubled = Position::new(pos.x * 2, pos.y * 2);
+        walls.insert(doubled);
+        if let Some(Tile::Pipe { shape, .. }) = grid.get(pos) {
+            let (direction1, direction2) = shape.directions();
+            walls.insert(doubled.step(direction1));
+            walls.insert(doubled.step(direction2));
+        }
+    }
+
+    // Start just outside the grid, so the border is reachable from every side
+    let inside_bounds =
+        |pos: &Position| (-1..=*width * 2).contains(&pos.x) && (-1..=*height * 2).contains(&pos.y);
+    let start = Position::new(-1, -1);
+    let mut outside: HashSet<Position> = HashSet::from([start]);
+    let mut queue = vec![start];
+    while let Some(pos) = queue.pop() {
+        for next in pos.neighbours() {
+            if inside_bounds(&next) && !walls.contains(&next) && outside.insert(next) {
+                queue.push(next);
+            }
+        }
+    }
+
+    grid.keys()
+        .filter(|pos| !main_loop.contains(pos))
+        .filter(|pos| !outside.contains(&Position::new(pos.x * 2, pos.y * 2)))
+        .copied()
+        .collect()
+}
+
+#[aoc(day10, part2)]
+fn part2(maze: &Maze) -> i32 {
+    enclosed(maze).len() as i32
     // 351
 }
 
+/// Which way the main loop runs when leaving the animal through its first connector
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    Clockwise,
+    CounterClockwise,
+}
+
+/// Everything known about the main loop and the tiles around it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Analysis {
+    /// Steps from the animal to every tile of the main loop, going either way around
+    pub distances: HashMap<Position, usize>,
+    /// The tile of the main loop farthest from the animal
+    pub farthest: Position,
+    pub orientation: Orientation,
+    /// Tiles that aren't part of the main loop but are enclosed by it
+    pub inside: HashSet<Position>,
+    /// Tiles that are neither part of the main loop nor enclosed by it
+    pub outside: HashSet<Position>,
+}
+
+/// Measure the main loop and classify every other tile as inside or outside of it
+pub fn analyze(maze: &Maze) -> Analysis {
+    let path = loop_path(maze);
+    let distances: HashMap<Position, usize> = path
+        .iter()
+        .enumerate()
+        .map(|(i, pos)| (*pos, i.min(path.len() - i)))
+        .collect();
+    // A loop on a grid always has an even length, so there is a single farthest tile
+    let farthest = path[path.len() / 2];
+
+    let orientation = if shoelace_doubled_signed(&path) > 0 {
+        Orientation::Clockwise
+    } else {
+        Orientation::CounterClockwise
+    };
+
+    let inside = enclosed(maze);
+    let outside = maze
+        .grid
+        .keys()
+        .filter(|pos| !maze.main_loop.contains(pos) && !inside.contains(pos))
+        .copied()
+        .collect();
+    Analysis {
+        distances,
+        farthest,
+        orientation,
+        inside,
+        outside,
+    }
+}
+
+/// How [`render`] tells the tiles apart
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    /// Only the main loop is drawn, with `S` for the animal, `I` for enclosed and `.` for other tiles
+    Plain,
+    /// Every pipe is drawn, with the main loop, the animal and enclosed tiles in ANSI colors
+    Ansi,
+}
+
+const BOLD_YELLOW: &str = "\x1b[1;33m";
+const BOLD_RED: &str = "\x1b[1;31m";
+const GREEN_BACKGROUND: &str = "\x1b[42m";
+const DIM: &str = "\x1b[2m";
+const RESET: &str = "\x1b[0m";
+
+/// Draw the maze with box-drawing characters, to show which tiles are enclosed by the main loop
+pub fn render(maze: &Maze, style: Style) -> String {
+    let enclosed = enclosed(maze);
+    let mut out = String::new();
+    for y in 0..maze.height {
+        for x in 0..maze.width {
+            let pos = Position::new(x, y);
+            let glyph = match maze.grid.get(&pos) {
+                Some(Tile::Pipe { shape, .. }) => shape.box_drawing(),
+                _ => '.',
+            };
+            let on_loop = maze.main_loop.contains(&pos);
+            match style {
+                Style::Plain if pos == maze.animal => out.push('S'),
+                Style::Plain if on_loop => out.push(glyph),
+                Style::Plain if enclosed.contains(&pos) => out.push('I'),
+                Style::Plain => out.push('.'),
+                Style::Ansi => {
+                    let (color, glyph) = if pos == maze.animal {
+                        (BOLD_RED, 'S')
+                    } else if on_loop {
+                        (BOLD_YELLOW, glyph)
+                    } else if enclosed.contains(&pos) {
+                        (GREEN_BACKGROUND, glyph)
+                    } else {
+                        (DIM, glyph)
+                    };
+                    out.push_str(&format!("{}{}{}", color, glyph, RESET));
+                }
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// The tiles of the main loop in order, starting at the animal
+fn loop_path(maze: &Maze) -> Vec<Position> {
+    let connectors = |pos: &Position| match maze.grid.get(pos) {
+        Some(Tile::Pipe { pos, shape }) => shape.connectors(pos),
+        _ => unreachable!("the main loop is closed when parsing"),
+    };
+    let mut path = vec![maze.animal];
+    let (mut previous, mut current) = (maze.animal, connectors(&maze.animal).0);
+    while current != maze.animal {
+        path.push(current);
+        let (connector1, connector2) = connectors(&current);
+        let next = if connector1 != previous {
+            connector1
+        } else {
+            connector2
+        };
+        previous = current;
+        current = next;
+    }
+    path
+}
+
+const CELL: f64 = 10.0;
+
+fn center(pos: &Position) -> (f64, f64) {
+    ((pos.x as f64 + 0.5) * CELL, (pos.y as f64 + 0.5) * CELL)
+}
+
+/// Draw the main loop, junk pipes and the enclosed tiles as an SVG image
+pub fn svg(maze: &Maze) -> Svg {
+    let (width, height) = (maze.width as f64 * CELL, maze.height as f64 * CELL);
+    let mut svg = Svg::new(width, height);
+    svg.rect(0.0, 0.0, width, height, SvgStyle::fill("white"));
+    let enclosed = enclosed(maze);
+    for y in 0..maze.height {
+        for x in 0..maze.width {
+            let pos = Position::new(x, y);
+            if enclosed.contains(&pos) {
+                let (cx, cy) = center(&pos);
+                let half = CELL / 2.0;
+                svg.rect(cx - half, cy - half, CELL, CELL, SvgStyle::fill("#9be39b"));
+            }
+            match maze.grid.get(&pos) {
+                Some(Tile::Pipe { shape, .. }) if !maze.main_loop.contains(&pos) => {
+                    // Junk pipes run from the middle of one side of the tile to another
+                    let (connector1, connector2) = shape.connectors(&pos);
+                    let (c, a, b) = (center(&pos), center(&connector1), center(&connector2));
+                    let side1 = ((c.0 + a.0) / 2.0, (c.1 + a.1) / 2.0);
+                    let side2 = ((c.0 + b.0) / 2.0, (c.1 + b.1) / 2.0);
+                    svg.polyline(&[side1, c, side2], SvgStyle::stroke("#bbbbbb", 1.0));
+                }
+                _ => (),
+            }
+        }
+    }
+    let vertices: Vec<(f64, f64)> = loop_path(maze).iter().map(center).collect();
+    svg.polygon(&vertices, SvgStyle::stroke("black", 2.0));
+    let (ax, ay) = center(&maze.animal);
+    svg.circle(ax, ay, CELL / 3.0, SvgStyle::fill("red"));
+    svg
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = Maze;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_maze(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(part1(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(part2(input).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
-    use super::*;
+    use proptest::prelude::*;
 
-    const INPUT: &str = "..F7.
-.FJ|.
-SJ.L7
-|F--J
-LJ...";
+    use super::*;
+    use crate::examples::{answer, example};
+    use crate::svg::assert_golden;
 
     #[test]
     fn test_part_1() {
-        assert_eq!(part1(&INPUT), 8);
+        let maze = parse_maze(&example(10, "part1")).unwrap();
+        assert_eq!(part1(&maze).to_string(), answer(10, "part1"));
     }
 
     #[test]
-    fn test_part_2_example1() {
-        assert_eq!(
-            part2(
-                "...........
-.S-------7.
-.|F-----7|.
-.||.....||.
-.||.....||.
-.|L-7.F-J|.
-.|..|.|..|.
-.L--J.L--J.
-..........."
-            ),
-            4
-        );
+    fn test_part_2() {
+        for name in ["part2", "part2-2", "part2-3", "part2-4"] {
+            let maze = parse_maze(&example(10, name)).unwrap();
+            assert_eq!(part2(&maze).to_string(), answer(10, name), "{}", name);
+        }
+        for name in ["part1", "part2", "part2-2", "part2-3", "part2-4"] {
+            let maze = parse_maze(&example(10, name)).unwrap();
+            assert_eq!(enclosed_by_flood_fill(&maze), enclosed(&maze), "{}", name);
+        }
     }
 
     #[test]
-    fn test_part_2_example1squeezed() {
+    fn test_render() {
+        let maze = parse_maze(&example(10, "part2-2")).unwrap();
         assert_eq!(
-            part2(
-                "..........
-.S------7.
-.|F----7|.
-.||....||.
-.||....||.
-.|L-7F-J|.
-.|..||..|.
-.L--JL--J.
-.........."
-            ),
-            4
+            render(&maze, Style::Plain),
+            "..........\n\
+             .S──────┐.\n\
+             .│┌────┐│.\n\
+             .││....││.\n\
+             .││....││.\n\
+             .│└─┐┌─┘│.\n\
+             .│II││II│.\n\
+             .└──┘└──┘.\n\
+             ..........\n"
         );
+
+        let maze = parse_maze(&example(10, "part2-3")).unwrap();
+        let ansi = render(&maze, Style::Ansi);
+        assert!(ansi.starts_with("\x1b[2m.\x1b[0m\x1b[1;33m┌\x1b[0m"));
+        assert_eq!(ansi.matches(GREEN_BACKGROUND).count(), 8);
+        assert_eq!(ansi.matches(BOLD_RED).count(), 1);
     }
 
     #[test]
-    fn test_part_2_example2() {
+    fn test_svg() {
+        let maze = parse_maze(&example(10, "part1")).unwrap();
+        assert_eq!(loop_path(&maze).len(), 16);
+        assert_eq!(loop_path(&maze)[0], Position::new(0, 2));
+        let maze = parse_maze(&example(10, "part2-3")).unwrap();
+        assert_golden(10, "part2-3", &svg(&maze));
+    }
+
+    #[test]
+    fn test_invalid_grid() {
+        let error = parse_maze("..F7.\n.FJ|.\nSJ.L7\n|F--J\nLJ..").unwrap_err();
+        assert_eq!((error.line, error.column), (5, 1));
+        let error = parse_maze("..F7.\n.FJ|.\nSJ.L7\n|F-xJ\nLJ...").unwrap_err();
+        assert_eq!((error.line, error.column), (4, 4));
+        let error = parse_maze("..F7.\n.FJ|.\nSJ.L7\n|F-|J\nLJ...").unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (4, 3, "-"));
+        assert_eq!(parse_maze(".....\n.F-7.").unwrap_err().line, 3);
+    }
+
+    #[test]
+    fn test_infer_animal() {
+        // All 4 neighbours point at the animal, only an F closes the loop
+        let maze = parse_maze(".|...\n-S-7.\n.|.|.\n.L-J.").unwrap();
+        assert_eq!((part1(&maze), part2(&maze)), (4, 1));
+        assert!(matches!(
+            maze.grid.get(&maze.animal),
+            Some(Tile::Pipe {
+                shape: Shape::SouthEast,
+                ..
+            })
+        ));
+
+        let error = parse_maze("F-7..\n|.|..\nL-S-7\n..|.|\n..L-J").unwrap_err();
         assert_eq!(
-            part2(
-                ".F----7F7F7F7F-7....
-.|F--7||||||||FJ....
-.||.FJ||||||||L7....
-FJL7L7LJLJ||LJ.L-7..
-L--J.L7...LJS7F-7L7.
-....F-J..F7FJ|L7L7L7
-....L7.F7||L7|.L7L7|
-.....|FJLJ|FJ|F7|.LJ
-....FJL-7.||.||||...
-....L---J.LJ.LJLJ..."
-            ),
-            8
+            (error.line, error.column, error.reason.as_str()),
+            (3, 3, "expected a single loop through the animal, found 2")
         );
+        let error = parse_maze("S-7\n..|\nL-J").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 1));
+        // Leaving north, the loop comes back from the east, so the animal can't sit on a |
+        let maze = parse_maze(".F7\n.SJ\n.|.\n.|.").unwrap();
+        assert_eq!(part1(&maze), 2);
     }
 
     #[test]
-    fn test_part_2_example3() {
+    fn test_analyze() {
+        let maze = parse_maze(&example(10, "part1")).unwrap();
+        let analysis = analyze(&maze);
+        assert_eq!(analysis.farthest, Position::new(4, 2));
+        assert_eq!(
+            analysis.distances[&analysis.farthest],
+            part1(&maze) as usize
+        );
+        assert_eq!(analysis.distances[&maze.animal], 0);
+        assert_eq!(analysis.distances.len(), maze.main_loop.len());
+        // S is an F, leaving south first runs counter-clockwise
+        assert_eq!(analysis.orientation, Orientation::CounterClockwise);
+        assert_eq!(analysis.inside.len(), 1);
         assert_eq!(
-            part2(
-                "FF7FSF7F7F7F7F7F---7
-L|LJ||||||||||||F--J
-FL-7LJLJ||||||LJL-77
-F--JF--7||LJLJ7F7FJ-
-L---JF-JLJ.||-FJLJJ7
-|F|F-JF---7F7-L7L|7|
-|FFJF7L7F-JF7|JL---7
-7-L-JL7||F7|L7F-7F7|
-L.L7LFJ|||||FJL7||LJ
-L7JLJL-JLJLJL--JLJ.L"
-            ),
-            10
+            analysis.inside.len() + analysis.outside.len() + maze.main_loop.len(),
+            25
         );
+
+        let maze = parse_maze(".F7\n.SJ\n.|.\n.|.").unwrap();
+        assert_eq!(analyze(&maze).orientation, Orientation::Clockwise);
+    }
+
+    /// A grid with a rectangular loop of `width` by `height` tiles, S in its top left corner
+    fn rectangle(width: usize, height: usize, left: usize, top: usize, margin: usize) -> String {
+        let grid_width = left + width + margin;
+        let mut rows = vec![".".repeat(grid_width); top];
+        for y in 0..height {
+            let row: String = (0..width)
+                .map(|x| match (x, y) {
+                    (0, 0) => 'S',
+                    (x, 0) if x == width - 1 => '7',
+                    (0, y) if y == height - 1 => 'L',
+                    (x, y) if x == width - 1 && y == height - 1 => 'J',
+                    (_, y) if y == 0 || y == height - 1 => '-',
+                    _ if x == 0 || x == width - 1 => '|',
+                    _ => '.',
+                })
+                .collect();
+            rows.push(format!("{}{}{}", ".".repeat(left), row, ".".repeat(margin)));
+        }
+        rows.extend(vec![".".repeat(grid_width); margin]);
+        rows.join("\n")
+    }
+
+    proptest! {
+        #[test]
+        fn test_rectangle(
+            width in 2..12usize,
+            height in 2..12usize,
+            left in 0..3usize,
+            top in 0..3usize,
+            margin in 0..3usize,
+        ) {
+            let maze = parse_maze(&rectangle(width, height, left, top, margin)).unwrap();
+            prop_assert_eq!(part1(&maze), (width + height - 2) as i32);
+            prop_assert_eq!(part2(&maze), ((width - 2) * (height - 2)) as i32);
+            prop_assert_eq!(enclosed_by_flood_fill(&maze), enclosed(&maze));
+        }
+
+        #[test]
+        fn test_flood_fill(seed in any::<u64>(), width in 3..20usize, height in 3..20usize) {
+            let input = crate::gen::pipe_maze(&mut crate::gen::Rng::new(seed), width, height, 0.5);
+            let maze = parse_maze(&input).unwrap();
+            prop_assert_eq!(enclosed_by_flood_fill(&maze), enclosed(&maze));
+        }
     }
 }
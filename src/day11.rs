@@ -1,128 +1,472 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
-use aoc_runner_derive::aoc;
+use aoc_runner_derive::{aoc, aoc_generator};
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Debug)]
-struct Position {
+use crate::answer::Answer;
+use crate::geometry::Point;
+use crate::parse::{lines, ParseError};
+use crate::solution::{Solution, SolveError};
+use crate::svg::{Style, Svg};
+
+type Position = Point<i128>;
+
+#[derive(Debug)]
+pub struct Image {
+    galaxies: Vec<Position>,
+    width: usize,
+    height: usize,
+}
+
+#[aoc_generator(day11)]
+fn parse_galaxies(content: &str) -> Result<Image, ParseError> {
+    let width = content.lines().next().map_or(0, |l| l.len());
+    let mut galaxies: Vec<Position> = Vec::new();
+    for line in lines(11, content) {
+        if line.text.len() != width {
+            return Err(line.error_line(format!("expected {} pixels", width)));
+        }
+        for (x, ch) in line.text.char_indices() {
+            match ch {
+                '#' => galaxies.push(Position::new(x as i128, line.number as i128 - 1)),
+                '.' => (),
+                _ => return Err(line.error(&line.text[x..], "expected '#' or '.'")),
+            }
+        }
+    }
+    Ok(Image {
+        galaxies,
+        width,
+        height: content.lines().count(),
+    })
+}
+
+/// How much the empty rows and columns of an image grow
+///
+/// An empty column becomes `x` columns wide and an empty row `y` rows high, so a factor of 1
+/// leaves the image as it is. Weights of specific empty columns or rows replace the factor.
+/// Factors and weights are at least 1, an image never shrinks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expansion {
     x: i128,
     y: i128,
+    columns: HashMap<usize, i128>,
+    rows: HashMap<usize, i128>,
+}
+
+impl Expansion {
+    /// Panics when a factor is less than 1
+    pub fn new(x: i128, y: i128) -> Expansion {
+        assert!(x >= 1 && y >= 1, "expected factors of at least 1");
+        Expansion {
+            x,
+            y,
+            columns: HashMap::new(),
+            rows: HashMap::new(),
+        }
+    }
+
+    pub fn uniform(factor: i128) -> Expansion {
+        Expansion::new(factor, factor)
+    }
+
+    /// Grow the empty column `x` to `weight` columns, columns with a galaxy never grow.
+    /// Panics when the weight is less than 1
+    pub fn with_column(mut self, x: usize, weight: i128) -> Expansion {
+        assert!(weight >= 1, "expected a weight of at least 1");
+        self.columns.insert(x, weight);
+        self
+    }
+
+    /// Grow the empty row `y` to `weight` rows, rows with a galaxy never grow.
+    /// Panics when the weight is less than 1
+    pub fn with_row(mut self, y: usize, weight: i128) -> Expansion {
+        assert!(weight >= 1, "expected a weight of at least 1");
+        self.rows.insert(y, weight);
+        self
+    }
 }
 
-impl Position {
-    fn mhdist(&self, other: &Position) -> i128 {
-        (self.x - other.x).abs() + (self.y - other.y).abs()
+/// The extra size of every line before `end`, for the lines without galaxies
+fn growth(
+    end: usize,
+    with_galaxy: &HashSet<i128>,
+    factor: i128,
+    weights: &HashMap<usize, i128>,
+) -> Vec<i128> {
+    let mut growth = vec![0; end + 1];
+    for line in 0..end {
+        let weight = if with_galaxy.contains(&(line as i128)) {
+            1
+        } else {
+            *weights.get(&line).unwrap_or(&factor)
+        };
+        growth[line + 1] = growth[line] + weight - 1;
     }
+    growth
 }
 
-fn get_sum_of_shortest_paths(content: &str, expand_factor: i128) -> i128 {
+/// The image after growing every empty row and column
+fn expand(image: &Image, expansion: &Expansion) -> Image {
     // 1. Get initial galaxy coordinates. Keep track of which x/y contain a galaxy
-    let mut x_with_galaxy: HashSet<i128> = HashSet::new();
-    let mut y_with_galaxy: HashSet<i128> = HashSet::new();
-    let raw_galaxies_vec: Vec<Vec<Position>> = content
-        .lines()
-        .enumerate()
-        .map(|(y, line)| {
-            line.chars()
-                .enumerate()
-                .filter(|(_, ch)| ch == &'#')
-                .map(|(x, _)| {
-                    let pos = Position {
-                        x: x as i128,
-                        y: y as i128,
-                    };
-                    x_with_galaxy.insert(pos.x);
-                    y_with_galaxy.insert(pos.y);
-                    pos
-                })
-                .collect::<Vec<Position>>()
-        })
-        .collect::<Vec<Vec<Position>>>();
-    // Flatten (wonder if .flatten_map() can be used to eliminate this step, not sure)
-    let raw_galaxies: Vec<Position> = raw_galaxies_vec
-        .into_iter()
-        .flatten()
-        .collect::<Vec<Position>>();
+    let raw_galaxies = &image.galaxies;
+    let x_with_galaxy: HashSet<i128> = raw_galaxies.iter().map(|p| p.x).collect();
+    let y_with_galaxy: HashSet<i128> = raw_galaxies.iter().map(|p| p.y).collect();
 
-    // 2. Determine all x and y to expand
-    let y_expansions: Vec<i128> = (0..(content.lines().count()))
-        .map(|y| y as i128)
-        .filter(|y| !y_with_galaxy.contains(y))
-        .collect::<Vec<i128>>();
-    let first_line = content.lines().next().unwrap();
-    let x_expansions: Vec<i128> = (0..(first_line.len()))
-        .map(|x| x as i128)
-        .filter(|x| !x_with_galaxy.contains(x))
-        .collect::<Vec<i128>>();
+    // 2. Determine how much the image grows before every x and y
+    let x_growth = growth(image.width, &x_with_galaxy, expansion.x, &expansion.columns);
+    let y_growth = growth(image.height, &y_with_galaxy, expansion.y, &expansion.rows);
 
     // 3. Shift galaxy coordinates based on expansions
     let galaxies = raw_galaxies
         .iter()
-        .map(|p| {
-            let xshift = x_expansions.iter().filter(|x| *x < &p.x).count() as i128;
-            let yshift = y_expansions.iter().filter(|y| *y < &p.y).count() as i128;
-            Position {
-                x: (p.x + (xshift * (expand_factor - 1).max(1))),
-                y: (p.y + (yshift * (expand_factor - 1).max(1))),
-            }
-        })
+        .map(|p| *p + Position::new(x_growth[p.x as usize], y_growth[p.y as usize]))
         .collect::<Vec<Position>>();
+    Image {
+        galaxies,
+        width: (image.width as i128 + x_growth[image.width]) as usize,
+        height: (image.height as i128 + y_growth[image.height]) as usize,
+    }
+}
 
-    // 4. Generate all galaxy combinations...
-    let mut combinations: HashSet<(&Position, &Position)> = HashSet::new();
-    for galaxy in &galaxies {
-        for other in &galaxies {
-            if galaxy == other {
-                continue;
-            }
-            // Create a tuple with the 2 galaxies sorted. This can probably be done in a much more performant way
-            let mut combination: Vec<&Position> = Vec::new();
-            combination.push(galaxy);
-            combination.push(other);
-            combination.sort();
-            combinations.insert((combination[0], combination[1]));
+/// The galaxies of an expanded image, to query the distances between them
+///
+/// Galaxies are numbered from 0 in the order of the image. A map holds the distances of one
+/// expansion, build a map per expansion from the same parsed image to compare them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GalaxyMap {
+    galaxies: Vec<Position>,
+}
+
+impl GalaxyMap {
+    pub fn new(image: &Image, expansion: &Expansion) -> GalaxyMap {
+        GalaxyMap {
+            galaxies: expand(image, expansion).galaxies,
+        }
+    }
+
+    pub fn galaxies(&self) -> &[Position] {
+        &self.galaxies
+    }
+
+    /// Length of the shortest path between two galaxies, panics when there is no such galaxy
+    pub fn distance(&self, from: usize, to: usize) -> i128 {
+        self.galaxies[from].manhattan(&self.galaxies[to])
+    }
+
+    /// Distances between every pair of galaxies, indexed by the galaxies' numbers
+    pub fn distance_matrix(&self) -> Vec<Vec<i128>> {
+        (0..self.galaxies.len())
+            .map(|from| {
+                (0..self.galaxies.len())
+                    .map(|to| self.distance(from, to))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// The `k` galaxies closest to `galaxy` and their distance, closest first
+    pub fn nearest(&self, galaxy: usize, k: usize) -> Vec<(usize, i128)> {
+        let mut others: Vec<(usize, i128)> = (0..self.galaxies.len())
+            .filter(|other| *other != galaxy)
+            .map(|other| (other, self.distance(galaxy, other)))
+            .collect();
+        others.sort_by_key(|(other, distance)| (*distance, *other));
+        others.truncate(k);
+        others
+    }
+
+    /// The two galaxies furthest apart, `None` without at least 2 galaxies
+    pub fn farthest_pair(&self) -> Option<(usize, usize)> {
+        if self.galaxies.len() < 2 {
+            return None;
         }
+        // The manhattan distance is the largest difference of x + y or of x - y between two points
+        let extremes = |key: fn(&Position) -> i128| {
+            let indices = 0..self.galaxies.len();
+            let min = indices
+                .clone()
+                .min_by_key(|i| key(&self.galaxies[*i]))
+                .unwrap();
+            let max = indices.max_by_key(|i| key(&self.galaxies[*i])).unwrap();
+            (min, max)
+        };
+        let sum = extremes(|p| p.x + p.y);
+        let difference = extremes(|p| p.x - p.y);
+        [sum, difference]
+            .into_iter()
+            .max_by_key(|(from, to)| self.distance(*from, *to))
     }
 
-    // ... and calculate manhattan distance for each combination and return the sum
-    combinations.iter().map(|(g1, g2)| g1.mhdist(g2)).sum()
+    pub fn sum_of_distances(&self) -> i128 {
+        (0..self.galaxies.len())
+            .flat_map(|from| (from + 1..self.galaxies.len()).map(move |to| (from, to)))
+            .map(|(from, to)| self.distance(from, to))
+            .sum()
+    }
 }
 
 #[aoc(day11, part1)]
-fn part1(content: &str) -> i128 {
-    get_sum_of_shortest_paths(content, 1)
+fn part1(image: &Image) -> i128 {
+    GalaxyMap::new(image, &Expansion::uniform(2)).sum_of_distances()
     // 9805264
 }
 
 #[aoc(day11, part2)]
-fn part2(content: &str) -> i128 {
-    get_sum_of_shortest_paths(content, 1_000_000)
+fn part2(image: &Image) -> i128 {
+    GalaxyMap::new(image, &Expansion::uniform(1_000_000)).sum_of_distances()
     // 779032247216
 }
 
+const CELL: f64 = 10.0;
+
+/// Space around the image, for the labels of galaxies on its edge
+const MARGIN: f64 = CELL / 2.0;
+
+fn center(pos: &Position) -> (f64, f64) {
+    (
+        MARGIN + (pos.x as f64 + 0.5) * CELL,
+        MARGIN + (pos.y as f64 + 0.5) * CELL,
+    )
+}
+
+/// Draw the expanded image as SVG, with the shortest path between two galaxies
+///
+/// `path` takes galaxy indices from 0 like [`GalaxyMap`], it defaults to the first and the last galaxy
+/// and is left out when the image has fewer than 2 galaxies. The labels number galaxies from 1 like
+/// the puzzle.
+pub fn svg(
+    image: &Image,
+    expansion: &Expansion,
+    path: Option<(usize, usize)>,
+) -> Result<Svg, String> {
+    let expanded = expand(image, expansion);
+    let count = expanded.galaxies.len();
+    let path = match path {
+        Some((from, to)) => match [from, to].into_iter().find(|&n| n >= count) {
+            Some(n) => return Err(format!("no galaxy {}, the image has {}", n, count)),
+            None => Some((from, to)),
+        },
+        None if count >= 2 => Some((0, count - 1)),
+        None => None,
+    };
+
+    let (width, height) = (expanded.width as f64 * CELL, expanded.height as f64 * CELL);
+    let (canvas_width, canvas_height) = (width + 2.0 * MARGIN, height + 2.0 * MARGIN);
+    let mut svg = Svg::new(canvas_width, canvas_height);
+    svg.rect(0.0, 0.0, canvas_width, canvas_height, Style::fill("white"));
+
+    // Shade the empty space, where the universe expanded
+    for x in 0..expanded.width {
+        if !expanded.galaxies.iter().any(|g| g.x == x as i128) {
+            let left = MARGIN + x as f64 * CELL;
+            svg.rect(left, MARGIN, CELL, height, Style::fill("#eeeeee"));
+        }
+    }
+    for y in 0..expanded.height {
+        if !expanded.galaxies.iter().any(|g| g.y == y as i128) {
+            let top = MARGIN + y as f64 * CELL;
+            svg.rect(MARGIN, top, width, CELL, Style::fill("#eeeeee"));
+        }
+    }
+
+    let ends = path.map(|(from, to)| (expanded.galaxies[from], expanded.galaxies[to]));
+    if let Some((from, to)) = ends {
+        let corner = Position::new(to.x, from.y);
+        svg.polyline(
+            &[center(&from), center(&corner), center(&to)],
+            Style::stroke("red", 2.0),
+        );
+    }
+    for (i, galaxy) in expanded.galaxies.iter().enumerate() {
+        let (cx, cy) = center(galaxy);
+        let color = match ends {
+            Some((from, to)) if *galaxy == from || *galaxy == to => "red",
+            _ => "#444444",
+        };
+        svg.circle(cx, cy, CELL / 3.0, Style::fill(color));
+        svg.text(
+            cx + CELL / 2.0,
+            cy - CELL / 2.0,
+            &(i + 1).to_string(),
+            Style::fill("black").with_font_size(8.0),
+        );
+    }
+    Ok(svg)
+}
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input = Image;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_galaxies(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(part1(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(part2(input).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
+    use proptest::prelude::*;
+
     use super::*;
+    use crate::examples::{answer, example};
+    use crate::svg::assert_golden;
+
+    #[test]
+    fn test_sum_of_distances_part_1() {
+        let image = parse_galaxies(&example(11, "part1")).unwrap();
+        assert_eq!(
+            GalaxyMap::new(&image, &Expansion::uniform(2))
+                .sum_of_distances()
+                .to_string(),
+            answer(11, "part1")
+        );
+    }
+
+    #[test]
+    fn test_sum_of_distances_part_2() {
+        let image = parse_galaxies(&example(11, "part1")).unwrap();
+        assert_eq!(
+            GalaxyMap::new(&image, &Expansion::uniform(10)).sum_of_distances(),
+            1030
+        );
+        assert_eq!(
+            GalaxyMap::new(&image, &Expansion::uniform(100))
+                .sum_of_distances()
+                .to_string(),
+            answer(11, "part2")
+        );
+    }
+
+    #[test]
+    fn test_expansion() {
+        let image = parse_galaxies(&example(11, "part1")).unwrap();
+        let sum = |expansion: Expansion| GalaxyMap::new(&image, &expansion).sum_of_distances();
+        // Empty columns and rows add up independently
+        assert_eq!(
+            sum(Expansion::new(10, 4)),
+            sum(Expansion::new(10, 1)) + sum(Expansion::new(1, 4)) - sum(Expansion::uniform(1))
+        );
+        assert_eq!(sum(Expansion::uniform(1)), 292);
+        // Empty columns 2, 5 and 8, and empty rows 3 and 7
+        let weighted = Expansion::uniform(1)
+            .with_column(2, 2)
+            .with_column(5, 2)
+            .with_column(8, 2)
+            .with_row(3, 2)
+            .with_row(7, 2);
+        assert_eq!(sum(weighted), sum(Expansion::uniform(2)));
+        // Galaxies are in column 0, a weight there doesn't count
+        assert_eq!(sum(Expansion::uniform(1).with_column(0, 5)), 292);
+        let expanded = expand(&image, &Expansion::new(1, 3).with_row(7, 1));
+        assert_eq!((expanded.width, expanded.height), (10, 12));
+    }
+
+    #[test]
+    #[should_panic(expected = "expected a weight of at least 1")]
+    fn test_shrinking_expansion() {
+        Expansion::uniform(2).with_row(7, 0);
+    }
+
+    #[test]
+    fn test_galaxy_map() {
+        let image = parse_galaxies(&example(11, "part1")).unwrap();
+        let map = GalaxyMap::new(&image, &Expansion::uniform(2));
+        assert_eq!(map.galaxies().len(), 9);
+        assert_eq!(map.sum_of_distances().to_string(), answer(11, "part1"));
+        // Galaxy 5 to 9, 1 to 7, 3 to 6 and 8 to 9 in the puzzle's numbering
+        assert_eq!(map.distance(4, 8), 9);
+        assert_eq!(map.distance(0, 6), 15);
+        assert_eq!(map.distance(2, 5), 17);
+        assert_eq!(map.distance(7, 8), 5);
+
+        let matrix = map.distance_matrix();
+        assert_eq!(matrix[4][8], 9);
+        assert_eq!(matrix[8][4], 9);
+        assert!((0..9).all(|i| matrix[i][i] == 0));
+
+        assert_eq!(map.nearest(7, 2), vec![(8, 5), (4, 6)]);
+        assert_eq!(map.nearest(7, 20).len(), 8);
+        let (from, to) = map.farthest_pair().unwrap();
+        assert_eq!(
+            map.distance(from, to),
+            *matrix.iter().flatten().max().unwrap()
+        );
 
-    const INPUT: &str = "...#......
-.......#..
-#.........
-..........
-......#...
-.#........
-.........#
-..........
-.......#..
-#...#.....";
+        let map = GalaxyMap::new(&image, &Expansion::uniform(100));
+        assert_eq!(map.sum_of_distances().to_string(), answer(11, "part2"));
+        let single = parse_galaxies("..#\n...").unwrap();
+        assert_eq!(
+            GalaxyMap::new(&single, &Expansion::uniform(2)).farthest_pair(),
+            None
+        );
+    }
 
     #[test]
-    fn test_get_sum_of_shortest_paths_part_1() {
-        assert_eq!(get_sum_of_shortest_paths(&INPUT, 1), 374);
+    fn test_invalid_image() {
+        let error = parse_galaxies("...#..\n..*...").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+        assert_eq!(parse_galaxies("...#..\n...").unwrap_err().line, 2);
     }
 
     #[test]
-    fn test_get_sum_of_shortest_paths_part_2() {
-        assert_eq!(get_sum_of_shortest_paths(&INPUT, 10), 1030);
-        assert_eq!(get_sum_of_shortest_paths(&INPUT, 100), 8410);
+    fn test_svg() {
+        let image = parse_galaxies(&example(11, "part1")).unwrap();
+        let expanded = expand(&image, &Expansion::uniform(2));
+        assert_eq!((expanded.width, expanded.height), (13, 12));
+        // Galaxy 4 to galaxy 8, 5 to 9 in the puzzle, is 9 steps
+        assert_eq!(expanded.galaxies[4].manhattan(&expanded.galaxies[8]), 9);
+        let expansion = Expansion::uniform(2);
+        assert_golden(11, "part1", &svg(&image, &expansion, Some((4, 8))).unwrap());
+        assert!(svg(&image, &expansion, Some((9, 0))).is_err());
+        assert!(svg(&image, &expansion, Some((4, 10))).is_err());
+
+        // Without 2 galaxies there is no default path
+        let empty = parse_galaxies("...\n...").unwrap();
+        assert!(!svg(&empty, &expansion, None)
+            .unwrap()
+            .to_string()
+            .contains("polyline"));
+        let single = parse_galaxies("...\n.#.").unwrap();
+        assert!(!svg(&single, &expansion, None)
+            .unwrap()
+            .to_string()
+            .contains("polyline"));
+        assert!(svg(&empty, &expansion, Some((0, 0))).is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn test_expansion_linearity(
+            rows in prop::collection::vec("[.#]{10}", 2..10),
+            factor in 2..1000i128,
+        ) {
+            let image = parse_galaxies(&rows.join("\n")).unwrap();
+            // Every empty row and column in between adds the same distance per factor step
+            let sum = |factor| GalaxyMap::new(&image, &Expansion::uniform(factor)).sum_of_distances();
+            let (base, step) = (sum(2), sum(3) - sum(2));
+            prop_assert_eq!(sum(factor), base + (factor - 2) * step);
+        }
+
+        #[test]
+        fn test_farthest_pair(rows in prop::collection::vec("[.#]{10}", 2..10), factor in 1..100i128) {
+            let image = parse_galaxies(&rows.join("\n")).unwrap();
+            let map = GalaxyMap::new(&image, &Expansion::uniform(factor));
+            let farthest = map.distance_matrix().into_iter().flatten().max();
+            let pair = map.farthest_pair().map(|(from, to)| map.distance(from, to));
+            match map.galaxies().len() {
+                0 | 1 => prop_assert_eq!(pair, None),
+                _ => prop_assert_eq!(pair, farthest),
+            }
+        }
     }
 }
@@ -0,0 +1,172 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Example input and expected answer from one part of a puzzle description
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Example {
+    pub part: u32,
+    /// The first `<pre><code>` block of the part, if it has one
+    pub input: Option<String>,
+    /// The last emphasized `<code><em>` of the part
+    pub answer: Option<String>,
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Remove all tags, like the highlights within example blocks
+fn strip_tags(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => (),
+        }
+    }
+    unescape(&text)
+}
+
+/// Offset and contents of every `start ... end` section
+fn sections<'a>(
+    html: &'a str,
+    start: &'a str,
+    end: &'a str,
+) -> impl Iterator<Item = (usize, &'a str)> {
+    html.match_indices(start).filter_map(move |(offset, _)| {
+        let rest = &html[offset + start.len()..];
+        rest.find(end).map(|length| (offset, &rest[..length]))
+    })
+}
+
+/// Pick the example of each part from a saved puzzle page
+pub fn extract(html: &str) -> Vec<Example> {
+    sections(html, "<article class=\"day-desc\">", "</article>")
+        .enumerate()
+        .map(|(i, (_, article))| {
+            let input = sections(article, "<pre><code>", "</code></pre>")
+                .next()
+                .map(|(_, input)| strip_tags(input));
+            let answer = sections(article, "<code><em>", "</em></code>")
+                .chain(sections(article, "<em><code>", "</code></em>"))
+                .max_by_key(|(offset, _)| *offset)
+                .map(|(_, answer)| strip_tags(answer));
+            Example {
+                part: i as u32 + 1,
+                input,
+                answer,
+            }
+        })
+        .collect()
+}
+
+/// `examples/<year>/day<N>` below `root`
+pub fn example_dir(root: &Path, year: u32, day: u32) -> PathBuf {
+    root.join("examples")
+        .join(year.to_string())
+        .join(format!("day{}", day))
+}
+
+/// Write the examples as `part<N>.txt` and `part<N>.answer` fixtures, returning the written files
+pub fn write_examples(dir: &Path, examples: &[Example]) -> io::Result<Vec<PathBuf>> {
+    fs::create_dir_all(dir)?;
+    let mut written = Vec::new();
+    for example in examples {
+        let files = [
+            ("txt", example.input.as_ref()),
+            ("answer", example.answer.as_ref()),
+        ];
+        for (extension, content) in files {
+            if let Some(content) = content {
+                let path = dir.join(format!("part{}.{}", example.part, extension));
+                fs::write(&path, format!("{}\n", content.trim_end()))?;
+                written.push(path);
+            }
+        }
+    }
+    Ok(written)
+}
+
+/// Example fixture `<name>.txt` of a 2023 day, for the test modules
+#[cfg(test)]
+pub(crate) fn example(day: u32, name: &str) -> String {
+    let path = example_dir(Path::new(env!("CARGO_MANIFEST_DIR")), 2023, day).join(name);
+    fs::read_to_string(path.with_extension("txt"))
+        .unwrap_or_else(|e| panic!("cannot read example {}: {}", path.display(), e))
+}
+
+/// Expected answer `<name>.answer` of a 2023 day's example
+#[cfg(test)]
+pub(crate) fn answer(day: u32, name: &str) -> String {
+    let path = example_dir(Path::new(env!("CARGO_MANIFEST_DIR")), 2023, day).join(name);
+    fs::read_to_string(path.with_extension("answer"))
+        .unwrap_or_else(|e| panic!("cannot read answer {}: {}", path.display(), e))
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::env;
+
+    use super::*;
+
+    const PAGE: &str = r#"<!DOCTYPE html>
+<html lang="en-us"><body><main>
+<article class="day-desc"><h2>--- Day 9: Mirage Maintenance ---</h2>
+<p>For example:</p>
+<pre><code>0 3 6 9 12 15
+1 3 6 10 <em>15</em> 21
+</code></pre>
+<p>The next value is <code><em>18</em></code>, so the sum is <code><em>114</em></code>.</p>
+</article>
+<p>Your puzzle answer was <code>1681758908</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2>
+<p>Extrapolating <code>a &lt; b</code> backwards gives <em><code>2</code></em>.</p>
+</article>
+</main></body></html>"#;
+
+    #[test]
+    fn test_extract() {
+        assert_eq!(
+            extract(PAGE),
+            vec![
+                Example {
+                    part: 1,
+                    input: Some("0 3 6 9 12 15\n1 3 6 10 15 21\n".to_string()),
+                    answer: Some("114".to_string()),
+                },
+                Example {
+                    part: 2,
+                    input: None,
+                    answer: Some("2".to_string()),
+                },
+            ]
+        );
+        assert_eq!(strip_tags("a &lt;<em>b</em>&amp;gt;"), "a <b&gt;");
+    }
+
+    #[test]
+    fn test_write_examples() {
+        let root = env::temp_dir().join(format!("aoc-examples-{}", std::process::id()));
+        let dir = example_dir(&root, 2023, 9);
+        let written = write_examples(&dir, &extract(PAGE)).unwrap();
+        assert_eq!(written.len(), 3);
+        assert_eq!(
+            fs::read_to_string(dir.join("part1.txt")).unwrap(),
+            "0 3 6 9 12 15\n1 3 6 10 15 21\n"
+        );
+        assert_eq!(fs::read_to_string(dir.join("part2.answer")).unwrap(), "2\n");
+        assert!(!dir.join("part2.txt").exists());
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,146 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::client::{is_unlocked, Client, ClientError};
+
+/// Environment variable holding the session token, takes precedence over the session file
+pub const SESSION_ENV: &str = "AOC_SESSION";
+
+/// `$XDG_CONFIG_HOME/aoc/session`, or `~/.config/aoc/session`
+pub fn default_session_file() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|config| config.join("aoc").join("session"))
+}
+
+/// Session token from the environment variable, or else from the session file
+pub fn session_token(session_file: Option<&Path>) -> Result<String, ClientError> {
+    read_session_token(env::var(SESSION_ENV).ok(), session_file)
+}
+
+fn read_session_token(
+    from_env: Option<String>,
+    session_file: Option<&Path>,
+) -> Result<String, ClientError> {
+    let token = match (from_env, session_file) {
+        (Some(token), _) => token,
+        (None, Some(path)) if path.exists() => fs::read_to_string(path)?,
+        (None, _) => return Err(ClientError::MissingSession),
+    };
+    // Accept the raw cookie as copied from the browser as well
+    let token = token.trim();
+    let token = token.strip_prefix("session=").unwrap_or(token);
+    if token.is_empty() {
+        return Err(ClientError::MissingSession);
+    }
+    Ok(token.to_string())
+}
+
+pub fn input_path(input_dir: &Path, day: u32) -> PathBuf {
+    input_dir.join(format!("day{}.txt", day))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fetched {
+    /// The input was already in the input directory
+    Cached,
+    Downloaded,
+}
+
+/// Make sure the input for a day is in `input_dir`, downloading it when it's unlocked
+pub fn fetch_input(
+    client: &mut Client,
+    year: u32,
+    day: u32,
+    input_dir: &Path,
+    now: SystemTime,
+) -> Result<Fetched, ClientError> {
+    let path = input_path(input_dir, day);
+    if path.exists() {
+        return Ok(Fetched::Cached);
+    }
+    if !is_unlocked(year, day, now) {
+        return Err(ClientError::Locked { year, day });
+    }
+    let input = client.input(year, day)?;
+    fs::create_dir_all(input_dir)?;
+    fs::write(path, input)?;
+    Ok(Fetched::Downloaded)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::time::Duration;
+
+    use super::*;
+    use crate::client::mock::MockServer;
+    use crate::client::unlock_time;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_session_token() {
+        let dir = temp_dir("session");
+        let file = dir.join("session");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&file, "session=abc123\n").unwrap();
+
+        assert_eq!(
+            read_session_token(Some("env".to_string()), Some(&file)).unwrap(),
+            "env"
+        );
+        assert_eq!(read_session_token(None, Some(&file)).unwrap(), "abc123");
+        assert!(matches!(
+            read_session_token(None, Some(&dir.join("missing"))),
+            Err(ClientError::MissingSession)
+        ));
+        assert!(matches!(
+            read_session_token(Some(" ".to_string()), None),
+            Err(ClientError::MissingSession)
+        ));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_fetch_input() {
+        let dir = temp_dir("fetch");
+        let server = MockServer::start(vec![(200, "0 3 6 9 12 15\n".to_string())]);
+        let mut client = Client::new("secret")
+            .with_base_url(&server.url)
+            .with_delay(Duration::ZERO);
+        let now = unlock_time(2023, 9);
+
+        assert_eq!(
+            fetch_input(&mut client, 2023, 9, &dir, now).unwrap(),
+            Fetched::Downloaded
+        );
+        // Neither a cached nor a locked day reaches the server
+        assert_eq!(
+            fetch_input(&mut client, 2023, 9, &dir, now).unwrap(),
+            Fetched::Cached
+        );
+        assert!(matches!(
+            fetch_input(&mut client, 2023, 10, &dir, now),
+            Err(ClientError::Locked {
+                year: 2023,
+                day: 10
+            })
+        ));
+
+        let requests = server.finish();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(
+            fs::read_to_string(input_path(&dir, 9)).unwrap(),
+            "0 3 6 9 12 15\n"
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
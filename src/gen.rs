@@ -0,0 +1,570 @@
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+
+use crate::geometry::{Direction, Point};
+
+type Position = Point<i32>;
+
+/// Small deterministic random number generator (SplitMix64), the same seed generates the same input
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in the non-empty `range`
+    pub fn range(&mut self, range: Range<i64>) -> i64 {
+        assert!(!range.is_empty(), "empty range {:?}", range);
+        let span = range.end.abs_diff(range.start);
+        range.start.wrapping_add((self.next_u64() % span) as i64)
+    }
+
+    /// Uniform below `n`, which must be positive
+    pub fn below(&mut self, n: usize) -> usize {
+        self.range(0..n as i64) as usize
+    }
+
+    /// True with probability `p`
+    pub fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+
+    /// `count` distinct values from `range`, in random order
+    pub fn sample(&mut self, range: Range<i64>, count: usize) -> Vec<i64> {
+        assert!(count as u64 <= range.end.abs_diff(range.start));
+        let mut seen = HashSet::new();
+        let mut values = Vec::with_capacity(count);
+        while values.len() < count {
+            let value = self.range(range.clone());
+            if seen.insert(value) {
+                values.push(value);
+            }
+        }
+        values
+    }
+}
+
+const LETTERS: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+const SPELLED_OUT: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+fn letters(rng: &mut Rng, max: usize) -> String {
+    (0..rng.below(max + 1))
+        .map(|_| *rng.pick(LETTERS) as char)
+        .collect()
+}
+
+/// Day 1: lines of letters with digits and spelled out digits, each line has at least one digit
+pub fn calibration_document(rng: &mut Rng, lines: usize) -> String {
+    (0..lines)
+        .map(|_| {
+            let pieces = 1 + rng.below(4);
+            let digit = rng.below(pieces);
+            let mut line = letters(rng, 5);
+            for piece in 0..pieces {
+                let value = 1 + rng.below(9);
+                if piece == digit || rng.chance(0.5) {
+                    line.push_str(&value.to_string());
+                } else {
+                    line.push_str(SPELLED_OUT[value - 1]);
+                }
+                line.push_str(&letters(rng, 5));
+            }
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Day 2: games of up to 6 reveals of each up to 3 colors
+pub fn games(rng: &mut Rng, count: usize) -> String {
+    (1..=count)
+        .map(|id| {
+            let reveals: Vec<String> = (0..1 + rng.below(6))
+                .map(|_| {
+                    let mut colors = ["red", "green", "blue"];
+                    rng.shuffle(&mut colors);
+                    colors[..1 + rng.below(3)]
+                        .iter()
+                        .map(|color| format!("{} {}", 1 + rng.below(20), color))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                })
+                .collect();
+            format!("Game {}: {}", id, reveals.join("; "))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+const SYMBOLS: &[u8] = b"*#+$/@=%&-";
+
+/// Day 3: a schematic of numbers with up to 3 digits, scattered symbols and '.'
+pub fn schematic(rng: &mut Rng, width: usize, height: usize) -> String {
+    (0..height)
+        .map(|_| {
+            let mut row = String::with_capacity(width);
+            while row.len() < width {
+                let room = width - row.len();
+                if rng.chance(0.15) {
+                    let digits = 1 + rng.below(3.min(room));
+                    let low = 10i64.pow(digits as u32 - 1);
+                    row.push_str(&rng.range(low..low * 10).to_string());
+                    if row.len() < width {
+                        row.push('.');
+                    }
+                } else if rng.chance(0.05) {
+                    row.push(*rng.pick(SYMBOLS) as char);
+                } else {
+                    row.push('.');
+                }
+            }
+            row
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn numbers(values: &[i64]) -> String {
+    values
+        .iter()
+        .map(|n| format!("{:2}", n))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Day 4: scratchcards of distinct numbers below 100
+///
+/// Most cards match nothing, so the copies won in part 2 grow linearly with the number of cards.
+pub fn cards(rng: &mut Rng, count: usize, winning: usize, have: usize) -> String {
+    assert!(
+        winning + have <= 99,
+        "expected at most 99 numbers on a card"
+    );
+    (1..=count)
+        .map(|id| {
+            let values = rng.sample(1..100, winning + have);
+            let matches = if rng.chance(0.6) { 0 } else { 1 + rng.below(3) };
+            let matches = matches.min(winning).min(have);
+            let (winning_numbers, rest) = values.split_at(winning);
+            let mut have_numbers: Vec<i64> = winning_numbers[..matches]
+                .iter()
+                .chain(&rest[..have - matches])
+                .copied()
+                .collect();
+            rng.shuffle(&mut have_numbers);
+            format!(
+                "Card {:3}: {} | {}",
+                id,
+                numbers(winning_numbers),
+                numbers(&have_numbers)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+const ALMANAC_MAPS: [&str; 7] = [
+    "seed-to-soil",
+    "soil-to-fertilizer",
+    "fertilizer-to-water",
+    "water-to-light",
+    "light-to-temperature",
+    "temperature-to-humidity",
+    "humidity-to-location",
+];
+const ALMANAC_SPAN: i64 = 1 << 32;
+
+/// Day 5: an almanac of seed ranges (start and length pairs) and 7 maps of `rules` rules each
+///
+/// The source ranges of a map don't overlap and neither do its destination ranges:
+/// every map shuffles the segments of one contiguous range.
+pub fn almanac(rng: &mut Rng, seed_ranges: usize, rules: usize) -> String {
+    let seeds: Vec<String> = (0..seed_ranges)
+        .flat_map(|_| {
+            let start = rng.range(0..ALMANAC_SPAN / 2);
+            [start, rng.range(1..ALMANAC_SPAN / 64)]
+        })
+        .map(|n| n.to_string())
+        .collect();
+    let maps: Vec<String> = ALMANAC_MAPS
+        .iter()
+        .map(|name| {
+            let mut bounds = rng.sample(0..ALMANAC_SPAN, rules + 1);
+            bounds.sort();
+            let mut segments: Vec<Range<i64>> = bounds.windows(2).map(|b| b[0]..b[1]).collect();
+            rng.shuffle(&mut segments);
+            let mut dest = bounds[0];
+            let mut lines = vec![format!("{} map:", name)];
+            for segment in segments {
+                let length = segment.end - segment.start;
+                lines.push(format!("{} {} {}", dest, segment.start, length));
+                dest += length;
+            }
+            lines.join("\n")
+        })
+        .collect();
+    format!("seeds: {}\n\n{}", seeds.join(" "), maps.join("\n\n"))
+}
+
+/// Day 6: up to 4 races that can each be won, more would overflow the race read without spaces
+pub fn races(rng: &mut Rng, count: usize) -> String {
+    assert!((1..=4).contains(&count), "expected 1 to 4 races");
+    let (times, records): (Vec<i64>, Vec<i64>) = (0..count)
+        .map(|_| {
+            let time = rng.range(7..100);
+            // Holding the button one ms longer beats the record
+            let pressed = rng.range(0..(time - 1) / 2);
+            (time, pressed * (time - pressed))
+        })
+        .unzip();
+    let column = |values: &[i64]| {
+        values
+            .iter()
+            .map(|n| format!("{:>6}", n))
+            .collect::<String>()
+    };
+    format!("Time:    {}\nDistance:{}", column(&times), column(&records))
+}
+
+const CARDS: &[u8] = b"AKQJT98765432";
+
+/// Day 7: distinct hands with bids up to 1000
+pub fn hands(rng: &mut Rng, count: usize) -> String {
+    assert!(count <= 13usize.pow(5), "expected at most 13^5 hands");
+    let mut seen = HashSet::new();
+    let mut lines = Vec::with_capacity(count);
+    while lines.len() < count {
+        let hand: String = (0..5).map(|_| *rng.pick(CARDS) as char).collect();
+        if seen.insert(hand.clone()) {
+            lines.push(format!("{} {}", hand, 1 + rng.below(1000)));
+        }
+    }
+    lines.join("\n")
+}
+
+const NODE_LETTERS: &[u8] = b"BCDEFGHIJKLMNOPQRSTUVWXY";
+
+/// Name of the `index`th node that isn't a start or an end, at least 3 letters without A or Z
+fn node_name(mut index: usize) -> String {
+    let mut name = Vec::new();
+    while name.len() < 3 || index > 0 {
+        name.push(NODE_LETTERS[index % NODE_LETTERS.len()]);
+        index /= NODE_LETTERS.len();
+    }
+    String::from_utf8(name).unwrap()
+}
+
+/// Two letter prefix of the `index`th start or end node
+fn node_prefix(index: usize) -> String {
+    [index / 26, index % 26]
+        .iter()
+        .map(|&i| LETTERS[i].to_ascii_uppercase() as char)
+        .collect()
+}
+
+/// Day 8: a network with a ghost for each of the `cycles` lengths
+///
+/// Ghost `g` reaches its Z node after `cycles[g]` steps and every `cycles[g]` steps after that,
+/// whichever way the instructions turn. The first ghost walks from AAA to ZZZ.
+pub fn ghost_network(rng: &mut Rng, cycles: &[usize], instructions: usize) -> String {
+    assert!(cycles.len() <= 26 * 26, "expected at most 676 ghosts");
+    assert!(cycles.iter().all(|&c| c > 0) && instructions > 0);
+    let mut rules = Vec::new();
+    let mut named = 0;
+    for (ghost, &cycle) in cycles.iter().enumerate() {
+        let mut nodes: Vec<String> = (named..named + cycle - 1).map(node_name).collect();
+        named += cycle - 1;
+        nodes.push(format!("{}Z", node_prefix(26 * 26 - 1 - ghost)));
+        rules.push(format!(
+            "{}A = ({}, {})",
+            node_prefix(ghost),
+            nodes[0],
+            nodes[0]
+        ));
+        for (i, node) in nodes.iter().enumerate() {
+            let next = &nodes[(i + 1) % nodes.len()];
+            rules.push(format!("{} = ({}, {})", node, next, next));
+        }
+    }
+    rng.shuffle(&mut rules);
+    let instructions: String = (0..instructions)
+        .map(|_| *rng.pick(b"LR") as char)
+        .collect();
+    format!("{}\n\n{}", instructions, rules.join("\n"))
+}
+
+/// Day 9: histories of `length` values of polynomials up to `degree`
+pub fn histories(rng: &mut Rng, count: usize, length: usize, degree: usize) -> String {
+    (0..count)
+        .map(|_| {
+            // The first value of every row of differences, the last row is constant
+            let mut differences: Vec<i64> = (0..=degree).map(|_| rng.range(-20..21)).collect();
+            let mut values = Vec::with_capacity(length);
+            for _ in 0..length {
+                values.push(differences[0].to_string());
+                for i in 0..degree {
+                    differences[i] += differences[i + 1];
+                }
+            }
+            values.join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The pipe connecting the two directions
+fn pipe(directions: [Direction; 2]) -> char {
+    let has = |direction| directions.contains(&direction);
+    match (
+        has(Direction::North),
+        has(Direction::East),
+        has(Direction::South),
+    ) {
+        (true, _, true) => '|',
+        (true, true, _) => 'L',
+        (true, _, _) => 'J',
+        (_, true, true) => 'F',
+        (_, false, true) => '7',
+        _ => '-',
+    }
+}
+
+/// Random spanning tree of a `width` by `height` grid of nodes, as its edges
+fn spanning_tree(rng: &mut Rng, width: i32, height: i32) -> Vec<(Position, Position)> {
+    let inside = |p: &Position| p.x >= 0 && p.y >= 0 && p.x < width && p.y < height;
+    let start = Position::new(
+        rng.below(width as usize) as i32,
+        rng.below(height as usize) as i32,
+    );
+    let mut tree = HashSet::from([start]);
+    let mut frontier: Vec<(Position, Position)> = start
+        .neighbours()
+        .into_iter()
+        .filter(inside)
+        .map(|next| (start, next))
+        .collect();
+    let mut edges = Vec::new();
+    while !frontier.is_empty() {
+        let (from, to) = frontier.swap_remove(rng.below(frontier.len()));
+        if tree.insert(to) {
+            edges.push((from, to));
+            frontier.extend(
+                to.neighbours()
+                    .into_iter()
+                    .filter(|next| inside(next) && !tree.contains(next))
+                    .map(|next| (to, next)),
+            );
+        }
+    }
+    edges
+}
+
+/// Day 10: a `width` by `height` field with a single loop and `junk` of the other tiles random pipes
+///
+/// The loop runs around a random tree, drawn with its nodes 2 tiles apart, so it winds through
+/// most of the field and never touches itself.
+pub fn pipe_maze(rng: &mut Rng, width: usize, height: usize, junk: f64) -> String {
+    assert!(width >= 2 && height >= 2, "expected room for a loop");
+    // Cells of the thickened tree; the loop runs along their corners, one tile per corner
+    let mut cells = HashSet::new();
+    for y in 0..height as i32 / 2 {
+        for x in 0..width as i32 / 2 {
+            cells.insert(Position::new(2 * x, 2 * y));
+        }
+    }
+    for (from, to) in spanning_tree(rng, width as i32 / 2, height as i32 / 2) {
+        cells.insert(from * 2 + (to - from));
+    }
+
+    // Every corner on the boundary of the tree lies on exactly 2 boundary edges
+    let mut boundary: HashMap<Position, Vec<Position>> = HashMap::new();
+    for cell in &cells {
+        for direction in Direction::ALL {
+            if cells.contains(&cell.step(direction)) {
+                continue;
+            }
+            let corner = |d: Direction| match d {
+                Direction::North => *cell,
+                Direction::East => *cell + Position::new(1, 0),
+                Direction::South => *cell + Position::new(1, 1),
+                Direction::West => *cell + Position::new(0, 1),
+            };
+            let (a, b) = (corner(direction), corner(direction.turn_right()));
+            boundary.entry(a).or_default().push(b);
+            boundary.entry(b).or_default().push(a);
+        }
+    }
+
+    let mut tiles: Vec<Vec<char>> = (0..height)
+        .map(|_| {
+            (0..width)
+                .map(|_| {
+                    if rng.chance(junk) {
+                        *rng.pick(b"|-LJF7") as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    for (corner, next) in &boundary {
+        let directions = next.iter().map(|n| {
+            *Direction::ALL
+                .iter()
+                .find(|&&d| corner.step(d) == *n)
+                .unwrap()
+        });
+        let directions: Vec<Direction> = directions.collect();
+        tiles[corner.y as usize][corner.x as usize] = pipe([directions[0], directions[1]]);
+    }
+
+    // Junk next to the animal must not look like it connects to it
+    let mut corners: Vec<&Position> = boundary.keys().collect();
+    corners.sort();
+    let animal = *corners[rng.below(corners.len())];
+    tiles[animal.y as usize][animal.x as usize] = 'S';
+    for neighbour in animal.neighbours() {
+        let on_field = neighbour.x >= 0
+            && neighbour.y >= 0
+            && (neighbour.x as usize) < width
+            && (neighbour.y as usize) < height;
+        if on_field && !boundary.contains_key(&neighbour) {
+            tiles[neighbour.y as usize][neighbour.x as usize] = '.';
+        }
+    }
+    tiles
+        .iter()
+        .map(|row| row.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Day 11: an image with galaxies at `density`, and about 1 in 10 rows and columns empty
+pub fn galaxy_map(rng: &mut Rng, width: usize, height: usize, density: f64) -> String {
+    let empty_columns: Vec<bool> = (0..width).map(|_| rng.chance(0.1)).collect();
+    (0..height)
+        .map(|_| {
+            let empty_row = rng.chance(0.1);
+            empty_columns
+                .iter()
+                .map(|&empty_column| {
+                    if !empty_row && !empty_column && rng.chance(density) {
+                        '#'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A random input for `day` that grows with `size`, roughly the number of lines, None for unknown days
+pub fn input(day: u32, rng: &mut Rng, size: usize) -> Option<String> {
+    let size = size.max(2);
+    Some(match day {
+        1 => calibration_document(rng, size),
+        2 => games(rng, size),
+        3 => schematic(rng, size, size),
+        4 => cards(rng, size, 10, 25),
+        5 => almanac(rng, 10, size),
+        6 => races(rng, size.min(4)),
+        7 => hands(rng, size),
+        8 => {
+            let cycles: Vec<usize> = (0..6).map(|_| size / 2 + 1 + rng.below(size)).collect();
+            ghost_network(rng, &cycles, 1 + size / 4)
+        }
+        9 => histories(rng, size, 21, 6),
+        10 => pipe_maze(rng, size, size, 0.5),
+        11 => galaxy_map(rng, size, size, 0.05),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::answer::Answer;
+    use crate::solution::{find, registry};
+
+    #[test]
+    fn test_rng() {
+        let values: Vec<u64> = (0..5).map(|_| Rng::new(7).next_u64()).collect();
+        assert!(values.iter().all(|&v| v == values[0]));
+        let mut rng = Rng::new(7);
+        assert!((0..1000).all(|_| (-3..4).contains(&rng.range(-3..4))));
+        let mut sample = rng.sample(10..20, 10);
+        sample.sort();
+        assert_eq!(sample, (10..20).collect::<Vec<_>>());
+        assert_eq!(input(12, &mut rng, 10), None);
+    }
+
+    #[test]
+    fn test_inputs() {
+        for seed in 0..5 {
+            for entry in registry() {
+                let mut rng = Rng::new(seed);
+                let content = input(entry.day, &mut rng, 40).unwrap();
+                for part in 1..=entry.parts {
+                    if let Err(e) = entry.run(&content, part) {
+                        panic!("day {} part {} seed {}: {}", entry.day, part, seed, e);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_ghost_network() {
+        let entry = find(2023, 8).unwrap();
+        let content = ghost_network(&mut Rng::new(1), &[4, 6, 1, 9], 3);
+        assert_eq!(entry.run(&content, 1).unwrap(), Answer::from(4));
+        assert_eq!(entry.run(&content, 2).unwrap(), Answer::from(36));
+    }
+
+    #[test]
+    fn test_pipe_maze() {
+        // A single node is a loop of 4 tiles around one cell
+        let content = pipe_maze(&mut Rng::new(3), 3, 2, 0.0);
+        let expected: Vec<String> = [0, 1, 4, 5]
+            .iter()
+            .map(|&i| {
+                let mut tiles: Vec<char> = "F7.\nLJ.".chars().collect();
+                tiles[i] = 'S';
+                tiles.into_iter().collect()
+            })
+            .collect();
+        assert!(expected.contains(&content), "{}", content);
+        let entry = find(2023, 10).unwrap();
+        for seed in 0..10 {
+            let content = pipe_maze(&mut Rng::new(seed), 12, 9, 0.7);
+            // A tree of 6 by 4 nodes is surrounded by a loop of 4 tiles per node
+            assert_eq!(entry.run(&content, 1).unwrap(), Answer::from(48));
+        }
+    }
+}
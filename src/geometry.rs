@@ -0,0 +1,257 @@
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+/// Signed integer types usable as a coordinate of a [`Point`]
+pub trait Coordinate:
+    Copy
+    + Ord
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn abs(self) -> Self;
+}
+
+macro_rules! impl_coordinate {
+    ($($t:ty),*) => {
+        $(
+            impl Coordinate for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn abs(self) -> Self {
+                    <$t>::abs(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_coordinate!(i8, i16, i32, i64, i128, isize);
+
+/// A point on a grid where x grows to the east and y grows to the south (like lines in a puzzle input)
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug, Default)]
+pub struct Point<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Point<T> {
+    pub const fn new(x: T, y: T) -> Point<T> {
+        Point { x, y }
+    }
+}
+
+impl<T: Coordinate> Point<T> {
+    /// The neighbouring point in the given direction
+    pub fn step(&self, direction: Direction) -> Point<T> {
+        *self + direction.delta()
+    }
+
+    /// The 4 orthogonally adjacent points, in the order of [`Direction::ALL`]
+    pub fn neighbours(&self) -> [Point<T>; 4] {
+        Direction::ALL.map(|direction| self.step(direction))
+    }
+
+    /// The 8 orthogonally and diagonally adjacent points, row by row
+    pub fn surrounding(&self) -> [Point<T>; 8] {
+        let (one, zero) = (T::ONE, T::ZERO);
+        [
+            Point::new(-one, -one),
+            Point::new(zero, -one),
+            Point::new(one, -one),
+            Point::new(-one, zero),
+            Point::new(one, zero),
+            Point::new(-one, one),
+            Point::new(zero, one),
+            Point::new(one, one),
+        ]
+        .map(|delta| *self + delta)
+    }
+
+    pub fn manhattan(&self, other: &Point<T>) -> T {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+
+    pub fn chebyshev(&self, other: &Point<T>) -> T {
+        (self.x - other.x).abs().max((self.y - other.y).abs())
+    }
+}
+
+impl<T: Coordinate> Add for Point<T> {
+    type Output = Point<T>;
+
+    fn add(self, other: Point<T>) -> Point<T> {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl<T: Coordinate> Sub for Point<T> {
+    type Output = Point<T>;
+
+    fn sub(self, other: Point<T>) -> Point<T> {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl<T: Coordinate> Mul<T> for Point<T> {
+    type Output = Point<T>;
+
+    fn mul(self, factor: T) -> Point<T> {
+        Point::new(self.x * factor, self.y * factor)
+    }
+}
+
+impl<T: Coordinate> Neg for Point<T> {
+    type Output = Point<T>;
+
+    fn neg(self) -> Point<T> {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+impl<T: Coordinate> AddAssign for Point<T> {
+    fn add_assign(&mut self, other: Point<T>) {
+        *self = *self + other;
+    }
+}
+
+impl<T: Coordinate> SubAssign for Point<T> {
+    fn sub_assign(&mut self, other: Point<T>) {
+        *self = *self - other;
+    }
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    /// All directions, clockwise starting at north
+    pub const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    pub fn turn_left(&self) -> Direction {
+        match self {
+            Direction::North => Direction::West,
+            Direction::East => Direction::North,
+            Direction::South => Direction::East,
+            Direction::West => Direction::South,
+        }
+    }
+
+    pub fn turn_right(&self) -> Direction {
+        match self {
+            Direction::North => Direction::East,
+            Direction::East => Direction::South,
+            Direction::South => Direction::West,
+            Direction::West => Direction::North,
+        }
+    }
+
+    pub fn opposite(&self) -> Direction {
+        match self {
+            Direction::North => Direction::South,
+            Direction::East => Direction::West,
+            Direction::South => Direction::North,
+            Direction::West => Direction::East,
+        }
+    }
+
+    /// Unit vector pointing in this direction
+    pub fn delta<T: Coordinate>(&self) -> Point<T> {
+        match self {
+            Direction::North => Point::new(T::ZERO, -T::ONE),
+            Direction::East => Point::new(T::ONE, T::ZERO),
+            Direction::South => Point::new(T::ZERO, T::ONE),
+            Direction::West => Point::new(-T::ONE, T::ZERO),
+        }
+    }
+}
+
+/// Twice the signed area of the polygon with the given vertices (in order, without repeating the first one) using
+/// the shoelace formula. With y growing to the south, the area is positive when the vertices run clockwise.
+pub fn shoelace_doubled_signed<T: Coordinate>(vertices: &[Point<T>]) -> T {
+    vertices
+        .iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .fold(T::ZERO, |acc, (a, b)| acc + (a.x * b.y - b.x * a.y))
+}
+
+/// Area of the polygon with the given vertices (in order, without repeating the first one) using the shoelace formula.
+/// Polygons on a grid with only horizontal/vertical edges always have an integral area; for other polygons a half
+/// is truncated.
+pub fn shoelace_area<T: Coordinate>(vertices: &[Point<T>]) -> T {
+    shoelace_doubled_signed(vertices).abs() / (T::ONE + T::ONE)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_distances() {
+        let a: Point<i32> = Point::new(1, 6);
+        let b: Point<i32> = Point::new(5, 11);
+        assert_eq!(a.manhattan(&b), 9);
+        assert_eq!(a.chebyshev(&b), 5);
+        assert_eq!(b.manhattan(&a), 9);
+    }
+
+    #[test]
+    fn test_directions() {
+        let origin: Point<i64> = Point::default();
+        for direction in Direction::ALL {
+            assert_eq!(direction.turn_left().turn_right(), direction);
+            assert_eq!(direction.opposite().opposite(), direction);
+            assert_eq!(direction.turn_right().turn_right(), direction.opposite());
+            assert_eq!(origin.step(direction).step(direction.opposite()), origin);
+        }
+        assert_eq!(origin.step(Direction::North), Point::new(0, -1));
+        assert_eq!(origin.neighbours()[1], Point::new(1, 0));
+        assert_eq!(origin.surrounding().len(), 8);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let mut p: Point<i128> = Point::new(2, -3);
+        assert_eq!(p + Point::new(1, 1), Point::new(3, -2));
+        assert_eq!(p - Point::new(1, 1), Point::new(1, -4));
+        assert_eq!(p * 3, Point::new(6, -9));
+        assert_eq!(-p, Point::new(-2, 3));
+        p += Point::new(10, 10);
+        p -= Point::new(2, 2);
+        assert_eq!(p, Point::new(10, 5));
+    }
+
+    #[test]
+    fn test_shoelace_area() {
+        let square: Vec<Point<i32>> = vec![
+            Point::new(0, 0),
+            Point::new(4, 0),
+            Point::new(4, 4),
+            Point::new(0, 4),
+        ];
+        assert_eq!(shoelace_area(&square), 16);
+        let reversed: Vec<Point<i32>> = square.iter().rev().copied().collect();
+        assert_eq!(shoelace_area(&reversed), 16);
+        let triangle: Vec<Point<i32>> = vec![Point::new(0, 0), Point::new(3, 0), Point::new(0, 3)];
+        assert_eq!(shoelace_area(&triangle), 4);
+        assert_eq!(shoelace_area::<i32>(&[]), 0);
+        assert_eq!(shoelace_doubled_signed(&square), 32);
+        assert_eq!(shoelace_doubled_signed(&reversed), -32);
+    }
+}
@@ -1,15 +1,29 @@
 use aoc_runner_derive::aoc_lib;
 
+pub mod answer;
+pub mod bench;
+pub mod client;
 mod day01;
-mod day02;
-mod day03;
+pub mod day02;
+pub mod day03;
 mod day04;
 mod day05;
 mod day06;
 mod day07;
 mod day08;
 mod day09;
-mod day10;
-mod day11;
+pub mod day10;
+pub mod day11;
+pub mod examples;
+pub mod fetch;
+pub mod gen;
+pub mod geometry;
+pub mod parse;
+#[cfg(test)]
+mod reference;
+pub mod runner;
+pub mod solution;
+pub mod submit;
+pub mod svg;
 
 aoc_lib! { year = 2023 }
@@ -1,3 +1,680 @@
-use aoc_runner_derive::aoc_main;
+use std::error::Error;
+use std::fs;
+use std::io::{self, IsTerminal, Read};
+use std::path::{Path, PathBuf};
+use std::process::{self, ExitCode};
+use std::time::{Duration, SystemTime};
 
-aoc_main! { lib = advent_of_code_2023 }
+use clap::{Args, Parser, Subcommand};
+use serde_json::{json, Value};
+
+use advent_of_code_2023::answer::Answer;
+use advent_of_code_2023::bench;
+use advent_of_code_2023::client::{self, is_unlocked, Client, ClientError};
+use advent_of_code_2023::examples;
+use advent_of_code_2023::fetch::{self, Fetched};
+use advent_of_code_2023::runner::{self, Timing};
+use advent_of_code_2023::solution::{self, Solution};
+use advent_of_code_2023::submit::{self, AttemptLog, Verdict};
+use advent_of_code_2023::svg::Svg;
+use advent_of_code_2023::{day03, day10, day11};
+
+const YEAR: u32 = 2023;
+
+/// Run my Advent of Code 2023 solutions
+#[derive(Parser, Debug)]
+#[command(version, about, args_conflicts_with_subcommands = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    run: RunArgs,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Download missing puzzle inputs into input/2023/
+    Fetch(FetchArgs),
+    /// Submit an answer, refusing answers that are already known to be wrong
+    Submit(SubmitArgs),
+    /// Extract the examples of a saved puzzle page into examples/2023/day<N>/
+    Examples(ExamplesArgs),
+    /// Time the solutions on the real inputs and compare them to the stored baseline
+    Bench(BenchArgs),
+    /// Draw a puzzle input the way the solution sees it
+    Draw(DrawArgs),
+}
+
+#[derive(Args, Debug)]
+struct RunArgs {
+    /// Day to run, can be repeated. Runs all implemented days by default
+    #[arg(short, long = "day", value_parser = clap::value_parser!(u32).range(1..=25))]
+    days: Vec<u32>,
+
+    /// Part to run. Runs all implemented parts by default
+    #[arg(short, long, value_parser = clap::value_parser!(u32).range(1..=2))]
+    part: Option<u32>,
+
+    /// Puzzle input to use instead of input/2023/day<N>.txt, or - to read it from stdin.
+    /// Requires selecting a single day
+    #[arg(short, long)]
+    input: Option<PathBuf>,
+
+    /// Print the results as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Args, Debug)]
+struct FetchArgs {
+    /// Day to fetch, can be repeated. Fetches all unlocked days by default
+    #[arg(short, long = "day", value_parser = clap::value_parser!(u32).range(1..=25))]
+    days: Vec<u32>,
+
+    #[command(flatten)]
+    session: SessionArgs,
+
+    /// Seconds to wait between two requests
+    #[arg(long, default_value = "1", value_parser = parse_delay)]
+    delay: Duration,
+}
+
+#[derive(Args, Debug)]
+struct SubmitArgs {
+    /// Day of the puzzle
+    #[arg(short, long, value_parser = clap::value_parser!(u32).range(1..=25))]
+    day: u32,
+
+    /// Part of the puzzle
+    #[arg(short, long, value_parser = clap::value_parser!(u32).range(1..=2))]
+    part: u32,
+
+    /// Answer to submit. Defaults to running the solution on input/2023/day<N>.txt
+    answer: Option<String>,
+
+    /// Log of submitted answers
+    #[arg(long, default_value = "input/2023/attempts.txt")]
+    log: PathBuf,
+
+    #[command(flatten)]
+    session: SessionArgs,
+}
+
+#[derive(Args, Debug)]
+struct ExamplesArgs {
+    /// Day of the puzzle
+    #[arg(short, long, value_parser = clap::value_parser!(u32).range(1..=25))]
+    day: u32,
+
+    /// Puzzle description saved from the website
+    page: PathBuf,
+}
+
+#[derive(Args, Debug)]
+struct BenchArgs {
+    /// Day to benchmark, can be repeated. Benchmarks all implemented days by default
+    #[arg(short, long = "day", value_parser = clap::value_parser!(u32).range(1..=25))]
+    days: Vec<u32>,
+
+    /// Part to benchmark. Benchmarks all implemented parts by default
+    #[arg(short, long, value_parser = clap::value_parser!(u32).range(1..=2))]
+    part: Option<u32>,
+
+    /// Timed runs per part
+    #[arg(long, default_value_t = bench::Config::default().samples)]
+    samples: usize,
+
+    /// Untimed runs per part before measuring
+    #[arg(long, default_value_t = bench::Config::default().warmup)]
+    warmup: usize,
+
+    /// Label of this machine, naming the baseline benchmarks/2023-<machine>.json
+    /// [default: the host name]
+    #[arg(long)]
+    machine: Option<String>,
+
+    /// Baseline to compare with instead of the one of the machine
+    #[arg(long)]
+    baseline: Option<PathBuf>,
+
+    /// Percentage by which the median may exceed the baseline before it's a regression
+    #[arg(long, default_value_t = 10.0)]
+    threshold: f64,
+
+    /// Store the results in the baseline, replacing the benchmarked parts
+    #[arg(long)]
+    save: bool,
+}
+
+#[derive(Args, Debug)]
+struct DrawArgs {
+    /// Day of the puzzle, day 10 can be drawn in the terminal, days 3, 10 and 11 as SVG
+    #[arg(short, long, value_parser = clap::value_parser!(u32).range(1..=25))]
+    day: u32,
+
+    /// Puzzle input to use instead of input/2023/day<N>.txt, or - to read it from stdin
+    #[arg(short, long)]
+    input: Option<PathBuf>,
+
+    /// Draw without colors, the default when the output is not a terminal
+    #[arg(long)]
+    plain: bool,
+
+    /// Write an SVG image to this file instead
+    #[arg(long)]
+    svg: Option<PathBuf>,
+
+    /// Galaxies to connect in the day 11 SVG, numbered from 1 like in the puzzle
+    /// [default: the first and the last]
+    #[arg(long, num_args = 2, value_names = ["FROM", "TO"], requires = "svg")]
+    path: Option<Vec<usize>>,
+}
+
+#[derive(Args, Debug)]
+struct SessionArgs {
+    /// File containing the session token, used when AOC_SESSION is not set
+    /// [default: ~/.config/aoc/session]
+    #[arg(long)]
+    session_file: Option<PathBuf>,
+}
+
+impl SessionArgs {
+    fn client(self) -> Result<Client, ClientError> {
+        let session_file = self.session_file.or_else(fetch::default_session_file);
+        if client::contact().is_none() {
+            eprintln!(
+                "set {} to an email address or URL, so the requests say whom to contact",
+                client::CONTACT_ENV
+            );
+        }
+        fetch::session_token(session_file.as_deref()).map(|session| Client::new(&session))
+    }
+}
+
+fn parse_delay(s: &str) -> Result<Duration, String> {
+    let seconds: f64 = s.parse().map_err(|e| format!("{}", e))?;
+    Duration::try_from_secs_f64(seconds).map_err(|e| format!("{}", e))
+}
+
+fn input_dir() -> PathBuf {
+    PathBuf::from(format!("input/{}", YEAR))
+}
+
+struct Outcome {
+    day: u32,
+    part: u32,
+    result: Result<(Answer, Timing), String>,
+}
+
+/// The (day, part) combinations to run
+fn select(days: &[u32], part: Option<u32>) -> Vec<(u32, u32)> {
+    let solutions: Vec<(u32, u32)> = runner::solutions().collect();
+    let mut days: Vec<u32> = if days.is_empty() {
+        solutions.iter().map(|(day, _)| *day).collect()
+    } else {
+        days.to_vec()
+    };
+    days.sort_unstable();
+    days.dedup();
+
+    days.iter()
+        .flat_map(|&day| {
+            let parts: Vec<u32> = match part {
+                Some(part) => vec![part],
+                None => solutions
+                    .iter()
+                    .filter(|(d, _)| *d == day)
+                    .map(|(_, p)| *p)
+                    .collect(),
+            };
+            // Keep days without any solution so they get reported
+            if parts.is_empty() {
+                vec![(day, 1)]
+            } else {
+                parts.into_iter().map(|part| (day, part)).collect()
+            }
+        })
+        .collect()
+}
+
+fn read_input(input: Option<&Path>, day: u32) -> Result<String, String> {
+    match input {
+        Some(path) if path == Path::new("-") => {
+            let mut content = String::new();
+            io::stdin()
+                .read_to_string(&mut content)
+                .map_err(|e| format!("cannot read input from stdin: {}", e))?;
+            Ok(content)
+        }
+        Some(path) => fs::read_to_string(path)
+            .map_err(|e| format!("cannot read input {}: {}", path.display(), e)),
+        None => {
+            let path = fetch::input_path(&input_dir(), day);
+            fs::read_to_string(&path)
+                .map_err(|e| format!("cannot read input {}: {}", path.display(), e))
+        }
+    }
+}
+
+fn print_text(outcome: &Outcome) {
+    match &outcome.result {
+        Ok((answer, timing)) => println!(
+            "Day {} - Part {}: {}\n\tgenerator: {:?},\n\trunner: {:?}\n",
+            outcome.day, outcome.part, answer, timing.generator, timing.runner
+        ),
+        Err(e) => eprintln!(
+            "Day {} - Part {}: FAILED: {}\n",
+            outcome.day, outcome.part, e
+        ),
+    }
+}
+
+fn to_json(outcome: &Outcome) -> Value {
+    match &outcome.result {
+        Ok((answer, timing)) => json!({
+            "day": outcome.day,
+            "part": outcome.part,
+            "answer": answer,
+            "generator_ns": timing.generator.as_nanos() as u64,
+            "runner_ns": timing.runner.as_nanos() as u64,
+        }),
+        Err(e) => json!({
+            "day": outcome.day,
+            "part": outcome.part,
+            "error": e,
+        }),
+    }
+}
+
+fn run(cli: RunArgs) -> ExitCode {
+    if cli.input.is_some() && cli.days.len() != 1 {
+        eprintln!("--input requires selecting a single --day");
+        return ExitCode::FAILURE;
+    }
+    let selection = select(&cli.days, cli.part);
+
+    if !cli.json {
+        println!("Advent of code {}", YEAR);
+    }
+
+    let mut stdin_input: Option<Result<String, String>> = None;
+    let mut outcomes: Vec<Outcome> = Vec::new();
+    for (day, part) in selection {
+        // Stdin can only be read once, so keep it around for every part
+        let input = match cli.input.as_deref() {
+            Some(path) if path == Path::new("-") => stdin_input
+                .get_or_insert_with(|| read_input(Some(path), day))
+                .clone(),
+            input => read_input(input, day),
+        };
+        let result =
+            input.and_then(|input| runner::run_timed(day, part, &input).map_err(|e| e.to_string()));
+        let outcome = Outcome { day, part, result };
+        if !cli.json {
+            print_text(&outcome);
+        }
+        outcomes.push(outcome);
+    }
+
+    if cli.json {
+        let results: Vec<Value> = outcomes.iter().map(to_json).collect();
+        println!("{}", serde_json::to_string_pretty(&results).unwrap());
+    }
+
+    if outcomes.iter().all(|outcome| outcome.result.is_ok()) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn fetch(args: FetchArgs) -> ExitCode {
+    let mut client = match args.session.client() {
+        Ok(client) => client.with_delay(args.delay),
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let now = SystemTime::now();
+    let days: Vec<u32> = if args.days.is_empty() {
+        (1..=25)
+            .filter(|&day| is_unlocked(YEAR, day, now))
+            .collect()
+    } else {
+        args.days
+    };
+
+    let mut success = true;
+    for day in days {
+        match fetch::fetch_input(&mut client, YEAR, day, &input_dir(), now) {
+            Ok(Fetched::Cached) => println!("Day {}: already have it", day),
+            Ok(Fetched::Downloaded) => println!("Day {}: downloaded", day),
+            Err(e) => {
+                eprintln!("Day {}: FAILED: {}", day, e);
+                success = false;
+                // Every further request would be rejected as well
+                if matches!(e, ClientError::NotLoggedIn) {
+                    break;
+                }
+            }
+        }
+    }
+
+    if success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn submit(args: SubmitArgs) -> ExitCode {
+    let answer = match args.answer {
+        Some(answer) => Answer::from(answer.trim()),
+        None => match read_input(None, args.day)
+            .and_then(|input| runner::run(args.day, args.part, &input).map_err(|e| e.to_string()))
+        {
+            Ok(answer) => answer,
+            Err(e) => {
+                eprintln!("Day {} - Part {}: FAILED: {}", args.day, args.part, e);
+                return ExitCode::FAILURE;
+            }
+        },
+    };
+    let mut log = match AttemptLog::open(&args.log) {
+        Ok(log) => log,
+        Err(e) => {
+            eprintln!("cannot read {}: {}", args.log.display(), e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut client = match args.session.client() {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    println!(
+        "Day {} - Part {}: submitting {}",
+        args.day, args.part, answer
+    );
+    let now = SystemTime::now();
+    match submit::submit(
+        &mut client,
+        &mut log,
+        YEAR,
+        args.day,
+        args.part,
+        &answer,
+        now,
+    ) {
+        Ok(reply) => {
+            println!("{}", reply);
+            if reply.verdict == Verdict::Correct {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn extract_examples(args: ExamplesArgs) -> ExitCode {
+    let html = match fs::read_to_string(&args.page) {
+        Ok(html) => html,
+        Err(e) => {
+            eprintln!("cannot read {}: {}", args.page.display(), e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let found = examples::extract(&html);
+    if found.is_empty() {
+        eprintln!("no puzzle description in {}", args.page.display());
+        return ExitCode::FAILURE;
+    }
+    let dir = examples::example_dir(Path::new(""), YEAR, args.day);
+    match examples::write_examples(&dir, &found) {
+        Ok(written) => {
+            for path in written {
+                println!("wrote {}", path.display());
+            }
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("cannot write examples to {}: {}", dir.display(), e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Host name of this machine, "local" when it's unknown
+fn hostname() -> String {
+    process::Command::new("hostname")
+        .output()
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "local".to_string())
+}
+
+fn benchmark(args: BenchArgs) -> ExitCode {
+    let path = args.baseline.unwrap_or_else(|| {
+        let machine = args.machine.unwrap_or_else(hostname);
+        PathBuf::from(format!("benchmarks/{}-{}.json", YEAR, machine))
+    });
+    let baseline = match bench::load_baseline(&path) {
+        Ok(baseline) => baseline,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let config = bench::Config {
+        warmup: args.warmup,
+        samples: args.samples,
+    };
+
+    let mut success = true;
+    let mut records = Vec::new();
+    for (day, part) in select(&args.days, args.part) {
+        let result = solution::find(YEAR, day)
+            .filter(|entry| part <= entry.parts)
+            .ok_or_else(|| format!("no solution for day {} part {}", day, part))
+            .and_then(|entry| {
+                let input = read_input(None, day)?;
+                let stats =
+                    bench::measure(entry, part, &input, config).map_err(|e| e.to_string())?;
+                Ok((bench::input_hash(&input), stats))
+            });
+        let (input, stats) = match result {
+            Ok(result) => result,
+            Err(e) => {
+                eprintln!("Day {} - Part {}: FAILED: {}\n", day, part, e);
+                success = false;
+                continue;
+            }
+        };
+        println!(
+            "Day {} - Part {}: median {:?}, p90 {:?}, p99 {:?} ({} samples)",
+            day, part, stats.median, stats.p90, stats.p99, stats.samples
+        );
+        let record = bench::Record {
+            day,
+            part,
+            input,
+            stats,
+        };
+        match bench::Comparison::find(&baseline, &record) {
+            Some(comparison) => {
+                let regression = comparison.is_regression(args.threshold / 100.0);
+                println!(
+                    "\tbaseline: {:?}, {:+.1}%{}\n",
+                    comparison.baseline,
+                    comparison.change() * 100.0,
+                    if regression { " REGRESSION" } else { "" }
+                );
+                success &= !regression;
+            }
+            None if baseline
+                .iter()
+                .any(|old| (old.day, old.part) == (day, part)) =>
+            {
+                println!("\tbaseline: another input, run with --save to replace it\n")
+            }
+            None => println!("\tbaseline: none, run with --save to record one\n"),
+        }
+        records.push(record);
+    }
+
+    if args.save {
+        if let Err(e) = bench::save_baseline(&path, &baseline, &records) {
+            eprintln!("cannot write {}: {}", path.display(), e);
+            return ExitCode::FAILURE;
+        }
+        println!("Saved the baseline to {}", path.display());
+    }
+
+    if success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// The input drawn for the terminal
+fn draw_text(day: u32, input: &str, style: day10::Style) -> Result<String, Box<dyn Error>> {
+    match day {
+        10 => Ok(day10::render(&day10::Day10::parse(input)?, style)),
+        _ => Err(format!("no terminal drawing for day {}", day).into()),
+    }
+}
+
+fn draw_svg(day: u32, input: &str, path: Option<&[usize]>) -> Result<Svg, Box<dyn Error>> {
+    match day {
+        3 => Ok(day03::svg(&day03::Day03::parse(input)?)),
+        10 => Ok(day10::svg(&day10::Day10::parse(input)?)),
+        11 => {
+            let image = day11::Day11::parse(input)?;
+            let expansion = day11::Expansion::uniform(2);
+            let count = day11::GalaxyMap::new(&image, &expansion).galaxies().len();
+            let path = match path {
+                Some(&[from, to]) => {
+                    match [from, to].into_iter().find(|n| !(1..=count).contains(n)) {
+                        Some(n) => {
+                            return Err(format!("no galaxy {}, the image has {}", n, count).into())
+                        }
+                        None => Some((from - 1, to - 1)),
+                    }
+                }
+                _ => None,
+            };
+            Ok(day11::svg(&image, &expansion, path)?)
+        }
+        _ => Err(format!("no SVG drawing for day {}", day).into()),
+    }
+}
+
+fn draw(args: DrawArgs) -> ExitCode {
+    let style = if args.plain || !io::stdout().is_terminal() {
+        day10::Style::Plain
+    } else {
+        day10::Style::Ansi
+    };
+    let result = read_input(args.input.as_deref(), args.day).and_then(|input| match &args.svg {
+        Some(path) => draw_svg(args.day, &input, args.path.as_deref())
+            .map_err(|e| e.to_string())
+            .and_then(|svg| {
+                fs::write(path, svg.to_string())
+                    .map_err(|e| format!("cannot write {}: {}", path.display(), e))
+            }),
+        None => draw_text(args.day, &input, style)
+            .map(|text| print!("{}", text))
+            .map_err(|e| e.to_string()),
+    });
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match cli.command {
+        Some(Command::Fetch(args)) => fetch(args),
+        Some(Command::Submit(args)) => submit(args),
+        Some(Command::Examples(args)) => extract_examples(args),
+        Some(Command::Bench(args)) => benchmark(args),
+        Some(Command::Draw(args)) => draw(args),
+        None => run(cli.run),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_select() {
+        assert_eq!(select(&[5], None), vec![(5, 1)]);
+        assert_eq!(select(&[6, 1], Some(2)), vec![(1, 2), (6, 2)]);
+        assert_eq!(select(&[1, 2, 1], Some(1)), vec![(1, 1), (2, 1)]);
+        assert_eq!(select(&[20], None), vec![(20, 1)]);
+        assert_eq!(select(&[], None).len(), runner::solutions().count());
+    }
+
+    #[test]
+    fn test_cli() {
+        let cli = Cli::parse_from(["aoc", "-d", "6", "--day", "7", "-p", "2", "--json"]);
+        assert!(cli.command.is_none());
+        let run = cli.run;
+        assert_eq!((run.days, run.part, run.json), (vec![6, 7], Some(2), true));
+        assert!(Cli::try_parse_from(["aoc", "-d", "26"]).is_err());
+        assert!(Cli::try_parse_from(["aoc", "-p", "3"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_fetch() {
+        let cli = Cli::parse_from(["aoc", "fetch", "-d", "3", "--delay", "0.5"]);
+        let Some(Command::Fetch(args)) = cli.command else {
+            panic!("expected the fetch command");
+        };
+        assert_eq!(
+            (args.days, args.delay),
+            (vec![3], Duration::from_millis(500))
+        );
+        assert!(Cli::try_parse_from(["aoc", "fetch", "--delay", "-1"]).is_err());
+        assert!(Cli::try_parse_from(["aoc", "-d", "3", "fetch"]).is_err());
+
+        let cli = Cli::parse_from(["aoc", "submit", "-d", "1", "-p", "2", "53312"]);
+        let Some(Command::Submit(args)) = cli.command else {
+            panic!("expected the submit command");
+        };
+        assert_eq!((args.day, args.part), (1, 2));
+        assert_eq!(args.answer.as_deref(), Some("53312"));
+        assert!(Cli::try_parse_from(["aoc", "submit", "-d", "1", "42"]).is_err());
+
+        let cli = Cli::parse_from(["aoc", "draw", "-d", "10", "--plain"]);
+        let Some(Command::Draw(args)) = cli.command else {
+            panic!("expected the draw command");
+        };
+        assert_eq!((args.day, args.input, args.plain), (10, None, true));
+        let cli = Cli::parse_from(["aoc", "draw", "-d", "3", "--svg", "day3.svg"]);
+        let Some(Command::Draw(args)) = cli.command else {
+            panic!("expected the draw command");
+        };
+        assert_eq!(args.svg, Some(PathBuf::from("day3.svg")));
+    }
+}
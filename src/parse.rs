@@ -0,0 +1,158 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// Error for malformed puzzle input, pointing at the offending text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: u32,
+    /// 1-based line number
+    pub line: usize,
+    /// 1-based column (in characters)
+    pub column: usize,
+    pub text: String,
+    pub reason: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "day {} input, line {}, column {}: {}, found {:?}",
+            self.day, self.line, self.column, self.reason, self.text
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+/// A single line of puzzle input that knows where it came from, so errors can point at it
+#[derive(Clone, Copy, Debug)]
+pub struct Line<'a> {
+    pub day: u32,
+    /// 1-based line number
+    pub number: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    /// Create an error for `part`, which should be a slice of this line's text
+    pub fn error(&self, part: &str, reason: impl Into<String>) -> ParseError {
+        ParseError {
+            day: self.day,
+            line: self.number,
+            column: self.column(part),
+            text: part.to_string(),
+            reason: reason.into(),
+        }
+    }
+
+    /// Create an error for the whole line
+    pub fn error_line(&self, reason: impl Into<String>) -> ParseError {
+        self.error(self.text, reason)
+    }
+
+    fn column(&self, part: &str) -> usize {
+        // Derive the column from where the slice starts in memory; fall back to the start of the line
+        let offset = (part.as_ptr() as usize).saturating_sub(self.text.as_ptr() as usize);
+        self.text
+            .get(..offset)
+            .map_or(1, |before| before.chars().count() + 1)
+    }
+
+    pub fn parse<T: FromStr>(&self, part: &'a str, what: &str) -> Result<T, ParseError> {
+        part.parse::<T>()
+            .map_err(|_| self.error(part, format!("expected {}", what)))
+    }
+
+    pub fn split_once(
+        &self,
+        part: &'a str,
+        delimiter: &str,
+    ) -> Result<(&'a str, &'a str), ParseError> {
+        part.split_once(delimiter)
+            .ok_or_else(|| self.error(part, format!("expected {:?}", delimiter)))
+    }
+
+    pub fn strip_prefix(&self, part: &'a str, prefix: &str) -> Result<&'a str, ParseError> {
+        part.strip_prefix(prefix)
+            .ok_or_else(|| self.error(part, format!("expected {:?}", prefix)))
+    }
+
+    pub fn strip_suffix(&self, part: &'a str, suffix: &str) -> Result<&'a str, ParseError> {
+        part.strip_suffix(suffix)
+            .ok_or_else(|| self.error(part, format!("expected {:?}", suffix)))
+    }
+}
+
+/// Iterate over the lines of a day's input
+pub fn lines(day: u32, content: &str) -> impl Iterator<Item = Line<'_>> {
+    content.lines().enumerate().map(move |(idx, text)| Line {
+        day,
+        number: idx + 1,
+        text,
+    })
+}
+
+/// Take the next line, or fail when the input ends early
+pub fn next_line<'a>(
+    day: u32,
+    content: &str,
+    lines: &mut impl Iterator<Item = Line<'a>>,
+    what: &str,
+) -> Result<Line<'a>, ParseError> {
+    lines
+        .next()
+        .ok_or_else(|| end_of_input(day, content, format!("expected {}", what)))
+}
+
+/// Error for input that is missing something after its last line
+pub fn end_of_input(day: u32, content: &str, reason: impl Into<String>) -> ParseError {
+    ParseError {
+        day,
+        line: content.lines().count() + 1,
+        column: 1,
+        text: String::new(),
+        reason: reason.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_error_position() {
+        let line = lines(2, "first\nGame 1: 3 purple").nth(1).unwrap();
+        let (_, reveal) = line.split_once(line.text, ": ").unwrap();
+        let (_, color) = line.split_once(reveal, " ").unwrap();
+        let error = line.error(color, "expected a color");
+        assert_eq!((error.line, error.column), (2, 11));
+        assert_eq!(
+            error.to_string(),
+            "day 2 input, line 2, column 11: expected a color, found \"purple\""
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let line = lines(9, "12 x3").next().unwrap();
+        let mut parts = line.text.split(' ');
+        assert_eq!(line.parse::<i32>(parts.next().unwrap(), "a number"), Ok(12));
+        let error = line
+            .parse::<i32>(parts.next().unwrap(), "a number")
+            .unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (4, "x3"));
+        assert_eq!(line.split_once(line.text, ":").unwrap_err().column, 1);
+    }
+
+    #[test]
+    fn test_end_of_input() {
+        let content = "Time: 7";
+        let mut iter = lines(6, content).skip(1);
+        let error = next_line(6, content, &mut iter, "a distance line").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+        assert_eq!(error.reason, "expected a distance line");
+    }
+}
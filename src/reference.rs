@@ -0,0 +1,517 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::answer::Answer;
+
+const SPELLED_OUT: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+type Grid = Vec<Vec<char>>;
+
+fn grid(input: &str) -> Grid {
+    input.lines().map(|line| line.chars().collect()).collect()
+}
+
+fn numbers(text: &str) -> Vec<i128> {
+    text.split_whitespace()
+        .map(|n| n.parse().unwrap())
+        .collect()
+}
+
+/// The digit starting at `i`, spelled out or not
+fn digit_at(line: &str, i: usize, spelled: bool) -> Option<i128> {
+    let c = line[i..].chars().next()?;
+    if let Some(digit) = c.to_digit(10) {
+        return Some(digit as i128);
+    }
+    if !spelled {
+        return None;
+    }
+    (1..=9)
+        .find(|&d| line[i..].starts_with(SPELLED_OUT[d - 1]))
+        .map(|d| d as i128)
+}
+
+fn day01(input: &str, spelled: bool) -> i128 {
+    input
+        .lines()
+        .map(|line| {
+            let digits: Vec<i128> = (0..line.len())
+                .filter_map(|i| digit_at(line, i, spelled))
+                .collect();
+            digits[0] * 10 + digits[digits.len() - 1]
+        })
+        .sum()
+}
+
+/// Largest number of cubes of each color revealed in each game
+fn day02_games(input: &str) -> Vec<(i128, HashMap<&str, i128>)> {
+    input
+        .lines()
+        .map(|line| {
+            let (game, reveals) = line.split_once(": ").unwrap();
+            let mut max = HashMap::new();
+            for cubes in reveals.split([';', ',']) {
+                let (count, color) = cubes.trim().split_once(' ').unwrap();
+                let count: i128 = count.parse().unwrap();
+                let entry = max.entry(color).or_insert(0);
+                *entry = count.max(*entry);
+            }
+            (game["Game ".len()..].parse().unwrap(), max)
+        })
+        .collect()
+}
+
+fn day02(input: &str, part: u32) -> i128 {
+    let games = day02_games(input);
+    let cubes = |max: &HashMap<&str, i128>, color| max.get(color).copied().unwrap_or(0);
+    if part == 1 {
+        games
+            .iter()
+            .filter(|(_, max)| {
+                cubes(max, "red") <= 12 && cubes(max, "green") <= 13 && cubes(max, "blue") <= 14
+            })
+            .map(|(id, _)| id)
+            .sum()
+    } else {
+        games
+            .iter()
+            .map(|(_, max)| cubes(max, "red") * cubes(max, "green") * cubes(max, "blue"))
+            .sum()
+    }
+}
+
+/// Numbers in the schematic with the positions of their digits
+fn day03_numbers(grid: &Grid) -> Vec<(i128, Vec<(i64, i64)>)> {
+    let mut found = Vec::new();
+    for (y, row) in grid.iter().enumerate() {
+        let mut x = 0;
+        while x < row.len() {
+            if !row[x].is_ascii_digit() {
+                x += 1;
+                continue;
+            }
+            let start = x;
+            while x < row.len() && row[x].is_ascii_digit() {
+                x += 1;
+            }
+            let value = row[start..x].iter().collect::<String>().parse().unwrap();
+            let digits = (start..x).map(|x| (x as i64, y as i64)).collect();
+            found.push((value, digits));
+        }
+    }
+    found
+}
+
+fn day03(input: &str, part: u32) -> i128 {
+    let grid = grid(input);
+    let numbers = day03_numbers(&grid);
+    let touches = |digits: &[(i64, i64)], (sx, sy): (i64, i64)| {
+        digits
+            .iter()
+            .any(|&(x, y)| (x - sx).abs() <= 1 && (y - sy).abs() <= 1)
+    };
+    let symbols: Vec<(char, (i64, i64))> = grid
+        .iter()
+        .enumerate()
+        .flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .filter(|(_, c)| !c.is_ascii_digit() && **c != '.')
+                .map(move |(x, &c)| (c, (x as i64, y as i64)))
+        })
+        .collect();
+    if part == 1 {
+        numbers
+            .iter()
+            .filter(|(_, digits)| symbols.iter().any(|&(_, s)| touches(digits, s)))
+            .map(|(value, _)| value)
+            .sum()
+    } else {
+        symbols
+            .iter()
+            .filter(|(c, _)| *c == '*')
+            .map(|&(_, s)| {
+                let adjacent: Vec<i128> = numbers
+                    .iter()
+                    .filter(|(_, digits)| touches(digits, s))
+                    .map(|(value, _)| *value)
+                    .collect();
+                if adjacent.len() == 2 {
+                    adjacent[0] * adjacent[1]
+                } else {
+                    0
+                }
+            })
+            .sum()
+    }
+}
+
+fn day04(input: &str, part: u32) -> i128 {
+    let matches: Vec<usize> = input
+        .lines()
+        .map(|line| {
+            let (winning, have) = line.split_once(':').unwrap().1.split_once('|').unwrap();
+            let winning = numbers(winning);
+            numbers(have).iter().filter(|n| winning.contains(n)).count()
+        })
+        .collect();
+    if part == 1 {
+        return matches
+            .iter()
+            .filter(|&&m| m > 0)
+            .map(|&m| 1 << (m - 1))
+            .sum();
+    }
+    // Scratch every single card, adding the copies it wins to the pile
+    let mut pile: VecDeque<usize> = (0..matches.len()).collect();
+    let mut scratched = 0;
+    while let Some(card) = pile.pop_front() {
+        scratched += 1;
+        pile.extend((card + 1..=card + matches[card]).filter(|&c| c < matches.len()));
+    }
+    scratched
+}
+
+fn day05(input: &str) -> i128 {
+    let mut sections = input.split("\n\n");
+    let seeds = numbers(sections.next().unwrap().strip_prefix("seeds:").unwrap());
+    let maps: Vec<Vec<Vec<i128>>> = sections
+        .map(|section| section.lines().skip(1).map(numbers).collect())
+        .collect();
+    seeds
+        .iter()
+        .map(|&seed| {
+            maps.iter().fold(seed, |value, rules| {
+                rules
+                    .iter()
+                    .find(|rule| rule[1] <= value && value < rule[1] + rule[2])
+                    .map_or(value, |rule| value - rule[1] + rule[0])
+            })
+        })
+        .min()
+        .unwrap()
+}
+
+fn day06(input: &str, part: u32) -> i128 {
+    let lines: Vec<&str> = input
+        .lines()
+        .map(|l| l.split_once(':').unwrap().1)
+        .collect();
+    let (times, records) = if part == 1 {
+        (numbers(lines[0]), numbers(lines[1]))
+    } else {
+        let kerned = |line: &str| vec![line.replace(' ', "").parse().unwrap()];
+        (kerned(lines[0]), kerned(lines[1]))
+    };
+    times
+        .iter()
+        .zip(records)
+        .map(|(&time, record)| {
+            (0..=time)
+                .filter(|pressed| (time - pressed) * pressed > record)
+                .count() as i128
+        })
+        .product()
+}
+
+/// Sorted counts of equal cards, a larger one is a stronger type
+fn day07_type(hand: &str) -> Vec<usize> {
+    let mut counts: Vec<usize> = hand
+        .chars()
+        .collect::<HashSet<_>>()
+        .iter()
+        .map(|&c| hand.chars().filter(|&h| h == c).count())
+        .collect();
+    counts.sort_by(|a, b| b.cmp(a));
+    counts
+}
+
+fn day07(input: &str, part: u32) -> i128 {
+    let order = if part == 1 {
+        "23456789TJQKA"
+    } else {
+        "J23456789TQKA"
+    };
+    let mut hands: Vec<(Vec<usize>, Vec<usize>, i128)> = input
+        .lines()
+        .map(|line| {
+            let (hand, bid) = line.split_once(' ').unwrap();
+            let hand_type = if part == 1 {
+                day07_type(hand)
+            } else {
+                // Try every card for all the jokers
+                order
+                    .chars()
+                    .map(|c| day07_type(&hand.replace('J', &c.to_string())))
+                    .max()
+                    .unwrap()
+            };
+            let strengths = hand.chars().map(|c| order.find(c).unwrap()).collect();
+            (hand_type, strengths, bid.parse().unwrap())
+        })
+        .collect();
+    hands.sort();
+    hands
+        .iter()
+        .enumerate()
+        .map(|(rank, (_, _, bid))| (rank as i128 + 1) * bid)
+        .sum()
+}
+
+fn day08(input: &str, part: u32) -> i128 {
+    let (instructions, rules) = input.split_once("\n\n").unwrap();
+    let network: HashMap<&str, (&str, &str)> = rules
+        .lines()
+        .map(|rule| {
+            let (from, to) = rule.split_once(" = ").unwrap();
+            let (left, right) = to.trim_matches(['(', ')']).split_once(", ").unwrap();
+            (from, (left, right))
+        })
+        .collect();
+    let (mut ghosts, done): (Vec<&str>, fn(&str) -> bool) = if part == 1 {
+        (vec!["AAA"], |node| node == "ZZZ")
+    } else {
+        let starts = network
+            .keys()
+            .filter(|n| n.ends_with('A'))
+            .copied()
+            .collect();
+        (starts, |node| node.ends_with('Z'))
+    };
+    // Walk every ghost at once until all of them stand on an end node
+    let mut steps = 0;
+    for instruction in instructions.chars().cycle() {
+        if ghosts.iter().all(|ghost| done(ghost)) {
+            break;
+        }
+        for ghost in ghosts.iter_mut() {
+            let (left, right) = network[ghost];
+            *ghost = if instruction == 'L' { left } else { right };
+        }
+        steps += 1;
+    }
+    steps
+}
+
+fn day09(input: &str, part: u32) -> i128 {
+    fn extrapolate(values: &[i128]) -> i128 {
+        if values.iter().all(|&v| v == 0) {
+            return 0;
+        }
+        let differences: Vec<i128> = values.windows(2).map(|w| w[1] - w[0]).collect();
+        values[values.len() - 1] + extrapolate(&differences)
+    }
+    input
+        .lines()
+        .map(|line| {
+            let mut values = numbers(line);
+            if part == 2 {
+                values.reverse();
+            }
+            extrapolate(&values)
+        })
+        .sum()
+}
+
+/// Offsets of the tiles a pipe connects to
+fn day10_connections(tile: char) -> Vec<(i64, i64)> {
+    match tile {
+        '|' => vec![(0, -1), (0, 1)],
+        '-' => vec![(-1, 0), (1, 0)],
+        'L' => vec![(0, -1), (1, 0)],
+        'J' => vec![(0, -1), (-1, 0)],
+        '7' => vec![(0, 1), (-1, 0)],
+        'F' => vec![(0, 1), (1, 0)],
+        _ => vec![],
+    }
+}
+
+fn day10(input: &str, part: u32) -> i128 {
+    let mut grid = grid(input);
+    let (height, width) = (grid.len() as i64, grid[0].len() as i64);
+    let tile = |grid: &Grid, (x, y): (i64, i64)| {
+        if x < 0 || y < 0 || x >= width || y >= height {
+            '.'
+        } else {
+            grid[y as usize][x as usize]
+        }
+    };
+    let connected = |grid: &Grid, (x, y): (i64, i64)| -> Vec<(i64, i64)> {
+        day10_connections(tile(grid, (x, y)))
+            .iter()
+            .map(|(dx, dy)| (x + dx, y + dy))
+            .collect()
+    };
+    let animal = (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .find(|&p| tile(&grid, p) == 'S')
+        .unwrap();
+    // The animal sits on the only pipe whose neighbours both connect back to it
+    let pipe = "|-LJ7F"
+        .chars()
+        .find(|&pipe| {
+            grid[animal.1 as usize][animal.0 as usize] = pipe;
+            connected(&grid, animal)
+                .iter()
+                .all(|&next| connected(&grid, next).contains(&animal))
+        })
+        .unwrap();
+    grid[animal.1 as usize][animal.0 as usize] = pipe;
+
+    // Breadth-first search from the animal along the pipes
+    let mut distance = HashMap::from([(animal, 0)]);
+    let mut queue = VecDeque::from([animal]);
+    while let Some(p) = queue.pop_front() {
+        for next in connected(&grid, p) {
+            if !distance.contains_key(&next) {
+                distance.insert(next, distance[&p] + 1);
+                queue.push_back(next);
+            }
+        }
+    }
+    if part == 1 {
+        return *distance.values().max().unwrap();
+    }
+
+    // Flood the outside on a grid of twice the resolution, with room to pass between pipes
+    let mut walls = HashSet::new();
+    for &p in distance.keys() {
+        walls.insert((2 * p.0 + 1, 2 * p.1 + 1));
+        for next in connected(&grid, p) {
+            walls.insert((p.0 + next.0 + 1, p.1 + next.1 + 1));
+        }
+    }
+    let mut outside = HashSet::from([(0, 0)]);
+    let mut queue = VecDeque::from([(0, 0)]);
+    while let Some((x, y)) = queue.pop_front() {
+        for next in [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)] {
+            let on_grid = next.0 >= 0 && next.1 >= 0 && next.0 <= 2 * width && next.1 <= 2 * height;
+            if on_grid && !walls.contains(&next) && outside.insert(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+    (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .filter(|p| !distance.contains_key(p) && !outside.contains(&(2 * p.0 + 1, 2 * p.1 + 1)))
+        .count() as i128
+}
+
+fn day11(input: &str, factor: i128) -> i128 {
+    let grid = grid(input);
+    let galaxies: Vec<(usize, usize)> = grid
+        .iter()
+        .enumerate()
+        .flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .filter(|(_, &c)| c == '#')
+                .map(move |(x, _)| (x, y))
+        })
+        .collect();
+    let empty_row = |y: usize| grid[y].iter().all(|&c| c == '.');
+    let empty_column = |x: usize| grid.iter().all(|row| row[x] == '.');
+    // Walk from every galaxy to every later one, one row or column at a time
+    let mut sum = 0;
+    for (i, &(x1, y1)) in galaxies.iter().enumerate() {
+        for &(x2, y2) in &galaxies[i + 1..] {
+            let columns = x1.min(x2)..x1.max(x2);
+            let rows = y1.min(y2)..y1.max(y2);
+            sum += columns
+                .map(|x| if empty_column(x) { factor } else { 1 })
+                .sum::<i128>();
+            sum += rows
+                .map(|y| if empty_row(y) { factor } else { 1 })
+                .sum::<i128>();
+        }
+    }
+    sum
+}
+
+/// Slow but obviously correct answer to `part` of `day`, straight from the puzzle text
+///
+/// None for days without a reference solution.
+pub fn solve(day: u32, part: u32, input: &str) -> Option<Answer> {
+    let answer = match (day, part) {
+        (1, _) => day01(input, part == 2),
+        (2, _) => day02(input, part),
+        (3, _) => day03(input, part),
+        (4, _) => day04(input, part),
+        (5, 1) => day05(input),
+        (6, _) => day06(input, part),
+        (7, _) => day07(input, part),
+        (8, _) => day08(input, part),
+        (9, _) => day09(input, part),
+        (10, _) => day10(input, part),
+        (11, 1) => day11(input, 2),
+        (11, 2) => day11(input, 1_000_000),
+        _ => return None,
+    };
+    Some(answer.into())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::examples::{answer, example};
+    use crate::gen::{self, Rng};
+    use crate::solution::registry;
+
+    /// A small random input, with small races and short ghost cycles to keep brute force fast
+    fn small_input(day: u32, rng: &mut Rng) -> String {
+        match day {
+            6 => {
+                let count = 1 + rng.below(2);
+                gen::races(rng, count)
+            }
+            8 => {
+                let cycles: Vec<usize> = (0..1 + rng.below(4)).map(|_| 1 + rng.below(8)).collect();
+                let instructions = 1 + rng.below(5);
+                gen::ghost_network(rng, &cycles, instructions)
+            }
+            _ => {
+                let size = 2 + rng.below(12);
+                gen::input(day, rng, size).unwrap()
+            }
+        }
+    }
+
+    #[test]
+    fn test_examples() {
+        assert_eq!(
+            solve(1, 2, &example(1, "part2")).unwrap().to_string(),
+            answer(1, "part2")
+        );
+        assert_eq!(
+            solve(7, 2, &example(7, "part1")).unwrap().to_string(),
+            answer(7, "part2")
+        );
+        assert_eq!(
+            solve(10, 2, &example(10, "part2-4")).unwrap().to_string(),
+            answer(10, "part2-4")
+        );
+        assert_eq!(solve(12, 1, ""), None);
+    }
+
+    #[test]
+    fn test_differential() {
+        for entry in registry() {
+            let mut rng = Rng::new(entry.day as u64);
+            for _ in 0..100 {
+                let input = small_input(entry.day, &mut rng);
+                for part in 1..=entry.parts {
+                    assert_eq!(
+                        entry.run(&input, part).unwrap(),
+                        solve(entry.day, part, &input).unwrap(),
+                        "day {} part {} of\n{}",
+                        entry.day,
+                        part,
+                        input
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use std::error::Error;
+use std::time::{Duration, Instant};
+
+use crate::answer::Answer;
+use crate::solution::{self, Entry};
+
+const YEAR: u32 = 2023;
+
+/// All implemented (day, part) combinations, in order
+pub fn solutions() -> impl Iterator<Item = (u32, u32)> {
+    solution::registry()
+        .iter()
+        .filter(|entry| entry.year == YEAR)
+        .flat_map(|entry| (1..=entry.parts).map(|part| (entry.day, part)))
+}
+
+fn find(day: u32, part: u32) -> Option<&'static Entry> {
+    solution::find(YEAR, day).filter(|entry| part >= 1 && part <= entry.parts)
+}
+
+/// Wall-clock time spent on parsing the input and on solving the puzzle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timing {
+    pub generator: Duration,
+    pub runner: Duration,
+}
+
+/// Parse the input and solve a day's part
+pub fn run(day: u32, part: u32, input: &str) -> Result<Answer, Box<dyn Error>> {
+    run_timed(day, part, input).map(|(answer, _)| answer)
+}
+
+/// Like [`run`], but also measures how long the generator and the solver took
+pub fn run_timed(day: u32, part: u32, input: &str) -> Result<(Answer, Timing), Box<dyn Error>> {
+    let entry =
+        find(day, part).ok_or_else(|| format!("no solution for day {} part {}", day, part))?;
+    let start_time = Instant::now();
+    let parsed = entry.parse(input)?;
+    let inter_time = Instant::now();
+    let answer = entry.solve(&parsed, part)?;
+    let final_time = Instant::now();
+    Ok((
+        answer,
+        Timing {
+            generator: inter_time - start_time,
+            runner: final_time - inter_time,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_run() {
+        assert_eq!(
+            run(6, 1, "Time: 7 15 30\nDistance: 9 40 200").unwrap(),
+            Answer::from(288)
+        );
+        assert_eq!(
+            run(6, 2, "Time: 7 15 30\nDistance: 9 40 200\n").unwrap(),
+            Answer::from(71503)
+        );
+        assert!(run(6, 1, "Time: 7 15 30").is_err());
+        assert!(run(5, 2, "").is_err());
+        assert_eq!(solutions().count(), 21);
+    }
+}
@@ -0,0 +1,159 @@
+use std::any::Any;
+use std::error::Error;
+use std::fmt;
+
+use crate::answer::Answer;
+use crate::parse::ParseError;
+use crate::{day01, day02, day03, day04, day05, day06, day07, day08, day09, day10, day11};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    Parse(ParseError),
+    /// The input doesn't have an answer
+    NoAnswer(&'static str),
+    /// The part isn't implemented
+    MissingPart(u32),
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SolveError::Parse(e) => write!(f, "{}", e),
+            SolveError::NoAnswer(reason) => write!(f, "no answer: {}", reason),
+            SolveError::MissingPart(part) => write!(f, "part {} is not implemented", part),
+        }
+    }
+}
+
+impl Error for SolveError {}
+
+impl From<ParseError> for SolveError {
+    fn from(e: ParseError) -> Self {
+        SolveError::Parse(e)
+    }
+}
+
+/// A day's puzzle, parsing the input once for both parts
+pub trait Solution {
+    type Input: 'static;
+
+    /// Number of implemented parts
+    const PARTS: u32 = 2;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+
+    fn part1(input: &Self::Input) -> Result<Answer, SolveError>;
+
+    fn part2(_input: &Self::Input) -> Result<Answer, SolveError> {
+        Err(SolveError::MissingPart(2))
+    }
+}
+
+/// Parsed input of a registered solution
+pub struct Parsed(Box<dyn Any>);
+
+fn parse_erased<S: Solution>(input: &str) -> Result<Parsed, ParseError> {
+    S::parse(input).map(|input| Parsed(Box::new(input)))
+}
+
+fn solve_erased<S: Solution>(parsed: &Parsed, part: u32) -> Result<Answer, SolveError> {
+    let input = parsed
+        .0
+        .downcast_ref::<S::Input>()
+        .expect("input was parsed by another solution");
+    match part {
+        1 => S::part1(input),
+        2 if S::PARTS >= 2 => S::part2(input),
+        _ => Err(SolveError::MissingPart(part)),
+    }
+}
+
+/// A solution in the registry, callable without knowing its input type
+pub struct Entry {
+    pub year: u32,
+    pub day: u32,
+    /// Number of implemented parts
+    pub parts: u32,
+    parse: fn(&str) -> Result<Parsed, ParseError>,
+    solve: fn(&Parsed, u32) -> Result<Answer, SolveError>,
+}
+
+impl Entry {
+    pub const fn new<S: Solution>(year: u32, day: u32) -> Entry {
+        Entry {
+            year,
+            day,
+            parts: S::PARTS,
+            parse: parse_erased::<S>,
+            solve: solve_erased::<S>,
+        }
+    }
+
+    pub fn parse(&self, input: &str) -> Result<Parsed, ParseError> {
+        (self.parse)(input)
+    }
+
+    /// Solve a part from input parsed by this entry
+    pub fn solve(&self, parsed: &Parsed, part: u32) -> Result<Answer, SolveError> {
+        (self.solve)(parsed, part)
+    }
+
+    pub fn run(&self, input: &str, part: u32) -> Result<Answer, SolveError> {
+        self.solve(&self.parse(input)?, part)
+    }
+}
+
+static REGISTRY: &[Entry] = &[
+    Entry::new::<day01::Day01>(2023, 1),
+    Entry::new::<day02::Day02>(2023, 2),
+    Entry::new::<day03::Day03>(2023, 3),
+    Entry::new::<day04::Day04>(2023, 4),
+    Entry::new::<day05::Day05>(2023, 5),
+    Entry::new::<day06::Day06>(2023, 6),
+    Entry::new::<day07::Day07>(2023, 7),
+    Entry::new::<day08::Day08>(2023, 8),
+    Entry::new::<day09::Day09>(2023, 9),
+    Entry::new::<day10::Day10>(2023, 10),
+    Entry::new::<day11::Day11>(2023, 11),
+];
+
+/// All registered solutions, ordered by year and day
+pub fn registry() -> &'static [Entry] {
+    REGISTRY
+}
+
+pub fn find(year: u32, day: u32) -> Option<&'static Entry> {
+    REGISTRY
+        .iter()
+        .find(|entry| entry.year == year && entry.day == day)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::examples::example;
+
+    #[test]
+    fn test_registry() {
+        assert!(registry()
+            .windows(2)
+            .all(|w| (w[0].year, w[0].day) < (w[1].year, w[1].day)));
+        assert!(find(2023, 12).is_none());
+
+        let entry = find(2023, 6).unwrap();
+        let parsed = entry.parse("Time: 7 15 30\nDistance: 9 40 200").unwrap();
+        assert_eq!(entry.solve(&parsed, 1), Ok(Answer::from(288)));
+        assert_eq!(entry.solve(&parsed, 2), Ok(Answer::from(71503)));
+        assert_eq!(entry.solve(&parsed, 3), Err(SolveError::MissingPart(3)));
+    }
+
+    #[test]
+    fn test_missing_part() {
+        let entry = find(2023, 5).unwrap();
+        assert_eq!(entry.parts, 1);
+        assert!(matches!(entry.run("", 1), Err(SolveError::Parse(_))));
+        let parsed = entry.parse(&example(5, "part1")).unwrap();
+        assert_eq!(entry.solve(&parsed, 2), Err(SolveError::MissingPart(2)));
+    }
+}
@@ -0,0 +1,559 @@
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::answer::Answer;
+use crate::client::{Client, ClientError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, without a hint about the direction
+    Wrong,
+    /// Submitted during the cooldown of a previous wrong answer
+    TooSoon,
+    /// The part was already solved, or its first part wasn't yet
+    WrongLevel,
+}
+
+impl Verdict {
+    const NAMES: [(Verdict, &'static str); 6] = [
+        (Verdict::Correct, "correct"),
+        (Verdict::TooHigh, "too-high"),
+        (Verdict::TooLow, "too-low"),
+        (Verdict::Wrong, "wrong"),
+        (Verdict::TooSoon, "too-soon"),
+        (Verdict::WrongLevel, "wrong-level"),
+    ];
+
+    fn name(self) -> &'static str {
+        Verdict::NAMES.iter().find(|(v, _)| *v == self).unwrap().1
+    }
+
+    fn from_name(name: &str) -> Option<Verdict> {
+        Verdict::NAMES
+            .iter()
+            .find(|(_, n)| *n == name)
+            .map(|(v, _)| *v)
+    }
+
+    fn is_wrong(self) -> bool {
+        matches!(self, Verdict::TooHigh | Verdict::TooLow | Verdict::Wrong)
+    }
+}
+
+/// What the server made of a submitted answer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reply {
+    pub verdict: Verdict,
+    /// How long to wait before the next answer may be submitted
+    pub wait: Option<Duration>,
+}
+
+impl fmt::Display for Reply {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self.verdict {
+            Verdict::Correct => "That's the right answer!",
+            Verdict::TooHigh => "That's not the right answer, it's too high",
+            Verdict::TooLow => "That's not the right answer, it's too low",
+            Verdict::Wrong => "That's not the right answer",
+            Verdict::TooSoon => "Answer was submitted too recently",
+            Verdict::WrongLevel => "Not solving the right level, is it already completed?",
+        };
+        write!(f, "{}", message)?;
+        if let Some(wait) = self.wait {
+            write!(f, " (wait {}s before trying again)", wait.as_secs())?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse a number of minutes as written on the answer page, e.g. "one" or "5"
+fn parse_minutes(word: &str) -> Option<u64> {
+    const WORDS: [&str; 10] = [
+        "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+    ];
+    match WORDS.iter().position(|w| w.eq_ignore_ascii_case(word)) {
+        Some(i) => Some(i as u64 + 1),
+        None => word.parse().ok(),
+    }
+}
+
+/// "You have 4m 32s left to wait" or "Please wait 5 minutes before trying again"
+fn parse_wait(page: &str) -> Option<Duration> {
+    if let Some((_, rest)) = page.split_once("You have ") {
+        let (left, _) = rest.split_once(" left to wait")?;
+        let mut seconds = 0;
+        for component in left.split_whitespace() {
+            let unit = match component.chars().last()? {
+                'h' => 3600,
+                'm' => 60,
+                's' => 1,
+                _ => return None,
+            };
+            seconds += component[..component.len() - 1].parse::<u64>().ok()? * unit;
+        }
+        return Some(Duration::from_secs(seconds));
+    }
+    let lower = page.to_lowercase();
+    let (_, rest) = lower.split_once("wait ")?;
+    let (amount, rest) = rest.split_once(' ')?;
+    if !rest.starts_with("minute") {
+        return None;
+    }
+    parse_minutes(amount).map(|minutes| Duration::from_secs(minutes * 60))
+}
+
+/// Interpret the page returned after posting an answer
+pub fn parse_reply(page: &str) -> Option<Reply> {
+    let verdict = if page.contains("That's the right answer") {
+        Verdict::Correct
+    } else if page.contains("That's not the right answer") {
+        if page.contains("your answer is too high") {
+            Verdict::TooHigh
+        } else if page.contains("your answer is too low") {
+            Verdict::TooLow
+        } else {
+            Verdict::Wrong
+        }
+    } else if page.contains("You gave an answer too recently") {
+        Verdict::TooSoon
+    } else if page.contains("You don't seem to be solving the right level") {
+        Verdict::WrongLevel
+    } else {
+        return None;
+    };
+    let wait = match verdict {
+        Verdict::Correct | Verdict::WrongLevel => None,
+        _ => parse_wait(page),
+    };
+    Some(Reply { verdict, wait })
+}
+
+/// A previously submitted answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attempt {
+    /// Seconds since the unix epoch
+    pub time: u64,
+    pub day: u32,
+    pub part: u32,
+    pub answer: Answer,
+    pub reply: Reply,
+}
+
+impl Attempt {
+    fn to_line(&self) -> String {
+        let wait = self
+            .reply
+            .wait
+            .map_or("-".to_string(), |wait| wait.as_secs().to_string());
+        format!(
+            "{} {} {} {} {} {}",
+            self.time,
+            self.day,
+            self.part,
+            self.reply.verdict.name(),
+            wait,
+            self.answer
+        )
+    }
+
+    fn from_line(line: &str) -> Option<Attempt> {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let [time, day, part, verdict, wait, answer] = fields[..] else {
+            return None;
+        };
+        let wait = match wait {
+            "-" => None,
+            wait => Some(Duration::from_secs(wait.parse().ok()?)),
+        };
+        Some(Attempt {
+            time: time.parse().ok()?,
+            day: day.parse().ok()?,
+            part: part.parse().ok()?,
+            answer: Answer::from(answer),
+            reply: Reply {
+                verdict: Verdict::from_name(verdict)?,
+                wait,
+            },
+        })
+    }
+}
+
+/// Reason to not send an answer to the server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Refusal {
+    AlreadySolved { answer: Answer },
+    KnownWrong,
+    KnownTooHigh { bound: Answer },
+    KnownTooLow { bound: Answer },
+    CoolingDown { wait: Duration },
+    InvalidAnswer,
+}
+
+impl fmt::Display for Refusal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Refusal::AlreadySolved { answer } => write!(f, "already solved with {}", answer),
+            Refusal::KnownWrong => write!(f, "this answer was already rejected"),
+            Refusal::KnownTooHigh { bound } => write!(f, "{} was already too high", bound),
+            Refusal::KnownTooLow { bound } => write!(f, "{} was already too low", bound),
+            Refusal::CoolingDown { wait } => {
+                write!(f, "wait {}s before submitting again", wait.as_secs())
+            }
+            Refusal::InvalidAnswer => write!(f, "answer must be a single non-empty word"),
+        }
+    }
+}
+
+/// Every answer submitted so far, backed by a local file
+#[derive(Debug)]
+pub struct AttemptLog {
+    path: PathBuf,
+    attempts: Vec<Attempt>,
+}
+
+impl AttemptLog {
+    /// Read the log at `path`, a missing file is an empty log
+    pub fn open(path: &Path) -> io::Result<AttemptLog> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e),
+        };
+        let attempts = content
+            .lines()
+            .filter(|line| !line.starts_with('#') && !line.trim().is_empty())
+            .map(|line| {
+                Attempt::from_line(line).ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("invalid attempt in {}: {:?}", path.display(), line),
+                    )
+                })
+            })
+            .collect::<io::Result<_>>()?;
+        Ok(AttemptLog {
+            path: path.to_path_buf(),
+            attempts,
+        })
+    }
+
+    pub fn attempts(&self) -> &[Attempt] {
+        &self.attempts
+    }
+
+    /// Check whether submitting `answer` could possibly be useful
+    pub fn check(&self, day: u32, part: u32, answer: &Answer, now: u64) -> Result<(), Refusal> {
+        if let Answer::Text(text) = answer {
+            if text.is_empty() || text.contains(char::is_whitespace) {
+                return Err(Refusal::InvalidAnswer);
+            }
+        }
+        // The cooldown applies to all puzzles, not only the one it was given for
+        if let Some(last) = self.attempts.last() {
+            if let Some(wait) = last.reply.wait {
+                let until = last.time + wait.as_secs();
+                if now < until {
+                    return Err(Refusal::CoolingDown {
+                        wait: Duration::from_secs(until - now),
+                    });
+                }
+            }
+        }
+
+        for attempt in self
+            .attempts
+            .iter()
+            .filter(|a| a.day == day && a.part == part)
+        {
+            let verdict = attempt.reply.verdict;
+            if verdict == Verdict::Correct {
+                return Err(Refusal::AlreadySolved {
+                    answer: attempt.answer.clone(),
+                });
+            }
+            if verdict.is_wrong() && attempt.answer == *answer {
+                return Err(Refusal::KnownWrong);
+            }
+            let (Answer::Int(number), Answer::Int(previous)) = (answer, &attempt.answer) else {
+                continue;
+            };
+            match verdict {
+                Verdict::TooHigh if number >= previous => {
+                    return Err(Refusal::KnownTooHigh {
+                        bound: attempt.answer.clone(),
+                    })
+                }
+                Verdict::TooLow if number <= previous => {
+                    return Err(Refusal::KnownTooLow {
+                        bound: attempt.answer.clone(),
+                    })
+                }
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+
+    /// Append an attempt to the log file
+    pub fn record(&mut self, attempt: Attempt) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let new_file = !self.path.exists();
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        if new_file {
+            writeln!(file, "# time day part verdict wait answer")?;
+        }
+        writeln!(file, "{}", attempt.to_line())?;
+        self.attempts.push(attempt);
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum SubmitError {
+    Refused(Refusal),
+    UnexpectedPage(String),
+    Client(ClientError),
+}
+
+impl fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SubmitError::Refused(refusal) => write!(f, "not submitting: {}", refusal),
+            SubmitError::UnexpectedPage(page) => {
+                write!(f, "could not understand the response: {}", page.trim())
+            }
+            SubmitError::Client(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for SubmitError {}
+
+impl From<ClientError> for SubmitError {
+    fn from(e: ClientError) -> Self {
+        SubmitError::Client(e)
+    }
+}
+
+impl From<io::Error> for SubmitError {
+    fn from(e: io::Error) -> Self {
+        SubmitError::Client(ClientError::Io(e))
+    }
+}
+
+/// Submit an answer unless the log shows it is pointless, and record the reply
+pub fn submit(
+    client: &mut Client,
+    log: &mut AttemptLog,
+    year: u32,
+    day: u32,
+    part: u32,
+    answer: &Answer,
+    now: SystemTime,
+) -> Result<Reply, SubmitError> {
+    let now = now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    log.check(day, part, answer, now)
+        .map_err(SubmitError::Refused)?;
+    let page = client.answer(year, day, part, &answer.to_string())?;
+    let reply = parse_reply(&page).ok_or(SubmitError::UnexpectedPage(page))?;
+    log.record(Attempt {
+        time: now,
+        day,
+        part,
+        answer: answer.clone(),
+        reply,
+    })?;
+    Ok(reply)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::env;
+
+    use super::*;
+    use crate::client::mock::MockServer;
+
+    fn page(message: &str) -> String {
+        format!(
+            "<!DOCTYPE html>\n<html><body><main>\n<article><p>{}</p></article>\n</main></body></html>\n",
+            message
+        )
+    }
+
+    const TOO_HIGH: &str = "That's not the right answer; your answer is too high.  If you're stuck, make sure you're using the full input data; there are also some general tips on the <a href=\"/2023/about\">about page</a>, or you can ask for hints on the <a href=\"https://www.reddit.com/r/adventofcode/\" target=\"_blank\">subreddit</a>.  Please wait one minute before trying again. <a href=\"/2023/day/1\">[Return to Day 1]</a>";
+    const TOO_SOON: &str = "You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 4m 32s left to wait. <a href=\"/2023/day/1\">[Return to Day 1]</a>";
+    const CORRECT: &str = "That's the right answer!  You are <span class=\"day-success\">one gold star</span> closer to restoring snow operations. <a href=\"/2023/day/1#part2\">[Continue to Part Two]</a>";
+
+    #[test]
+    fn test_parse_reply() {
+        assert_eq!(
+            parse_reply(&page(TOO_HIGH)),
+            Some(Reply {
+                verdict: Verdict::TooHigh,
+                wait: Some(Duration::from_secs(60))
+            })
+        );
+        assert_eq!(
+            parse_reply(&page(TOO_SOON)),
+            Some(Reply {
+                verdict: Verdict::TooSoon,
+                wait: Some(Duration::from_secs(272))
+            })
+        );
+        assert_eq!(
+            parse_reply(&page(
+                "That's not the right answer.  Please wait 5 minutes before trying again."
+            )),
+            Some(Reply {
+                verdict: Verdict::Wrong,
+                wait: Some(Duration::from_secs(300))
+            })
+        );
+        assert_eq!(
+            parse_reply(&page(CORRECT)).unwrap().verdict,
+            Verdict::Correct
+        );
+        assert_eq!(parse_reply(&page("Something else")), None);
+    }
+
+    #[test]
+    fn test_check() {
+        let attempt = |time, part, verdict, answer: &str| Attempt {
+            time,
+            day: 1,
+            part,
+            answer: Answer::from(answer),
+            reply: Reply {
+                verdict,
+                wait: Some(Duration::from_secs(60)),
+            },
+        };
+        let log = AttemptLog {
+            path: PathBuf::new(),
+            attempts: vec![
+                attempt(0, 1, Verdict::TooHigh, "500"),
+                attempt(100, 1, Verdict::TooLow, "100"),
+                attempt(200, 1, Verdict::Wrong, "abc"),
+                attempt(300, 2, Verdict::Correct, "42"),
+            ],
+        };
+        assert_eq!(log.check(1, 1, &Answer::from("250"), 400), Ok(()));
+        assert_eq!(
+            log.check(1, 1, &Answer::from("250"), 310),
+            Err(Refusal::CoolingDown {
+                wait: Duration::from_secs(50)
+            })
+        );
+        assert_eq!(
+            log.check(1, 1, &Answer::from("600"), 400),
+            Err(Refusal::KnownTooHigh {
+                bound: Answer::from(500)
+            })
+        );
+        assert_eq!(
+            log.check(1, 1, &Answer::from("99"), 400),
+            Err(Refusal::KnownTooLow {
+                bound: Answer::from(100)
+            })
+        );
+        assert_eq!(
+            log.check(1, 1, &Answer::from("abc"), 400),
+            Err(Refusal::KnownWrong)
+        );
+        assert_eq!(
+            log.check(1, 2, &Answer::from("43"), 400),
+            Err(Refusal::AlreadySolved {
+                answer: Answer::from(42)
+            })
+        );
+        assert_eq!(
+            log.check(1, 1, &Answer::from("1 2"), 400),
+            Err(Refusal::InvalidAnswer)
+        );
+    }
+
+    #[test]
+    fn test_submit() {
+        let dir = env::temp_dir().join(format!("aoc-submit-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("attempts.txt");
+        let server = MockServer::start(vec![(200, page(TOO_HIGH)), (200, page(CORRECT))]);
+        let mut client = Client::new("secret")
+            .with_base_url(&server.url)
+            .with_delay(Duration::ZERO);
+        let mut log = AttemptLog::open(&path).unwrap();
+        let start = UNIX_EPOCH + Duration::from_secs(1701406800);
+
+        let reply = submit(
+            &mut client,
+            &mut log,
+            2023,
+            1,
+            1,
+            &Answer::from(54000),
+            start,
+        )
+        .unwrap();
+        assert_eq!(reply.verdict, Verdict::TooHigh);
+        // Refused locally, without reaching the server
+        assert!(matches!(
+            submit(
+                &mut client,
+                &mut log,
+                2023,
+                1,
+                1,
+                &Answer::from(53386),
+                start
+            ),
+            Err(SubmitError::Refused(Refusal::CoolingDown { .. }))
+        ));
+        let later = start + Duration::from_secs(60);
+        assert!(matches!(
+            submit(
+                &mut client,
+                &mut log,
+                2023,
+                1,
+                1,
+                &Answer::from(54001),
+                later
+            ),
+            Err(SubmitError::Refused(Refusal::KnownTooHigh { .. }))
+        ));
+        let reply = submit(
+            &mut client,
+            &mut log,
+            2023,
+            1,
+            1,
+            &Answer::from(53386),
+            later,
+        )
+        .unwrap();
+        assert_eq!(reply.verdict, Verdict::Correct);
+
+        let requests = server.finish();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].request_line, "POST /2023/day/1/answer HTTP/1.1");
+        assert_eq!(requests[0].body, "level=1&answer=54000");
+
+        let reopened = AttemptLog::open(&path).unwrap();
+        assert_eq!(reopened.attempts(), log.attempts());
+        assert_eq!(reopened.attempts().len(), 2);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
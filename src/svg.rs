@@ -0,0 +1,214 @@
+use std::fmt;
+
+/// Presentation attributes of an SVG element, shapes without a fill are only outlined
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Style<'a> {
+    pub fill: Option<&'a str>,
+    pub stroke: Option<&'a str>,
+    pub stroke_width: Option<f64>,
+    pub font_size: Option<f64>,
+}
+
+impl<'a> Style<'a> {
+    pub fn fill(color: &'a str) -> Style<'a> {
+        Style {
+            fill: Some(color),
+            ..Style::default()
+        }
+    }
+
+    pub fn stroke(color: &'a str, width: f64) -> Style<'a> {
+        Style {
+            stroke: Some(color),
+            stroke_width: Some(width),
+            ..Style::default()
+        }
+    }
+
+    pub fn with_font_size(self, size: f64) -> Style<'a> {
+        Style {
+            font_size: Some(size),
+            ..self
+        }
+    }
+
+    fn attributes(&self) -> String {
+        let mut attributes = format!(" fill=\"{}\"", escape(self.fill.unwrap_or("none")));
+        if let Some(stroke) = self.stroke {
+            attributes.push_str(&format!(" stroke=\"{}\"", escape(stroke)));
+        }
+        if let Some(width) = self.stroke_width {
+            attributes.push_str(&format!(" stroke-width=\"{}\"", width));
+        }
+        if let Some(size) = self.font_size {
+            attributes.push_str(&format!(" font-size=\"{}\"", size));
+        }
+        attributes
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '&' => "&amp;".to_string(),
+            '<' => "&lt;".to_string(),
+            '>' => "&gt;".to_string(),
+            '"' => "&quot;".to_string(),
+            '\'' => "&apos;".to_string(),
+            _ => c.to_string(),
+        })
+        .collect()
+}
+
+fn points(points: &[(f64, f64)]) -> String {
+    points
+        .iter()
+        .map(|(x, y)| format!("{},{}", x, y))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// An SVG image built from shapes, drawn in the order they were added
+///
+/// The output only depends on the shapes, so images can be compared with golden files.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Svg {
+    width: f64,
+    height: f64,
+    elements: Vec<String>,
+}
+
+impl Svg {
+    pub fn new(width: f64, height: f64) -> Svg {
+        Svg {
+            width,
+            height,
+            elements: Vec::new(),
+        }
+    }
+
+    pub fn rect(&mut self, x: f64, y: f64, width: f64, height: f64, style: Style) -> &mut Svg {
+        self.elements.push(format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"{}/>",
+            x,
+            y,
+            width,
+            height,
+            style.attributes()
+        ));
+        self
+    }
+
+    pub fn circle(&mut self, cx: f64, cy: f64, r: f64, style: Style) -> &mut Svg {
+        self.elements.push(format!(
+            "<circle cx=\"{}\" cy=\"{}\" r=\"{}\"{}/>",
+            cx,
+            cy,
+            r,
+            style.attributes()
+        ));
+        self
+    }
+
+    pub fn line(&mut self, from: (f64, f64), to: (f64, f64), style: Style) -> &mut Svg {
+        self.elements.push(format!(
+            "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"{}/>",
+            from.0,
+            from.1,
+            to.0,
+            to.1,
+            style.attributes()
+        ));
+        self
+    }
+
+    /// An open path through the points
+    pub fn polyline(&mut self, vertices: &[(f64, f64)], style: Style) -> &mut Svg {
+        self.elements.push(format!(
+            "<polyline points=\"{}\"{}/>",
+            points(vertices),
+            style.attributes()
+        ));
+        self
+    }
+
+    /// A closed path through the points
+    pub fn polygon(&mut self, vertices: &[(f64, f64)], style: Style) -> &mut Svg {
+        self.elements.push(format!(
+            "<polygon points=\"{}\"{}/>",
+            points(vertices),
+            style.attributes()
+        ));
+        self
+    }
+
+    /// Text centered on (x, y)
+    pub fn text(&mut self, x: f64, y: f64, text: &str, style: Style) -> &mut Svg {
+        self.elements.push(format!(
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\" \
+             font-family=\"monospace\"{}>{}</text>",
+            x,
+            y,
+            style.attributes(),
+            escape(text)
+        ));
+        self
+    }
+}
+
+impl fmt::Display for Svg {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" \
+             viewBox=\"0 0 {0} {1}\">",
+            self.width, self.height
+        )?;
+        for element in &self.elements {
+            writeln!(f, "  {}", element)?;
+        }
+        writeln!(f, "</svg>")
+    }
+}
+
+/// Compare an image with its golden file in `examples/2023/day<N>/`, or update the file when
+/// `UPDATE_GOLDEN` is set
+#[cfg(test)]
+pub(crate) fn assert_golden(day: u32, name: &str, svg: &Svg) {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join(format!("examples/2023/day{}/{}.svg", day, name));
+    let actual = svg.to_string();
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&path, &actual).unwrap();
+    }
+    let expected = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("cannot read {}: {}", path.display(), e));
+    assert!(
+        actual == expected,
+        "{} differs from the golden file, rerun with UPDATE_GOLDEN=1 to accept it",
+        path.display()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_svg() {
+        let mut svg = Svg::new(20.0, 10.0);
+        svg.rect(0.0, 0.0, 20.0, 10.0, Style::fill("white"))
+            .polyline(&[(1.0, 1.0), (2.5, 1.0)], Style::stroke("red", 0.5))
+            .text(5.0, 5.0, "<&>", Style::fill("black").with_font_size(8.0));
+        assert_eq!(
+            svg.to_string(),
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"20\" height=\"10\" viewBox=\"0 0 20 10\">\n\
+             \x20 <rect x=\"0\" y=\"0\" width=\"20\" height=\"10\" fill=\"white\"/>\n\
+             \x20 <polyline points=\"1,1 2.5,1\" fill=\"none\" stroke=\"red\" stroke-width=\"0.5\"/>\n\
+             \x20 <text x=\"5\" y=\"5\" text-anchor=\"middle\" dominant-baseline=\"central\" \
+             font-family=\"monospace\" fill=\"black\" font-size=\"8\">&lt;&amp;&gt;</text>\n\
+             </svg>\n"
+        );
+    }
+}
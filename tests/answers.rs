@@ -0,0 +1,86 @@
+//! Checks every solution against the recorded answer for the real puzzle input.
+//! Inputs are not part of the repository, days without an input file are skipped.
+
+use std::fs;
+use std::path::PathBuf;
+
+use advent_of_code_2023::answer::Answer;
+use advent_of_code_2023::runner;
+
+fn root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+/// Recorded (part, answer) pairs for a day
+fn known_answers(day: u32) -> Vec<(u32, Answer)> {
+    let content = fs::read_to_string(root().join("answers/2023.txt")).unwrap();
+    content
+        .lines()
+        .filter(|line| !line.starts_with('#') && !line.trim().is_empty())
+        .map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields[..] {
+                [d, p, answer] => (d.parse::<u32>().unwrap(), p.parse::<u32>().unwrap(), answer),
+                _ => panic!(
+                    "expected 'day part answer' in answers file, found {:?}",
+                    line
+                ),
+            }
+        })
+        .filter(|(d, _, _)| *d == day)
+        .map(|(_, p, answer)| (p, Answer::from(answer)))
+        .collect()
+}
+
+fn check_day(day: u32) {
+    let path = root().join(format!("input/2023/day{}.txt", day));
+    let Ok(input) = fs::read_to_string(&path) else {
+        eprintln!("skipping day {}: no input at {}", day, path.display());
+        return;
+    };
+
+    let answers = known_answers(day);
+    assert!(!answers.is_empty(), "no recorded answers for day {}", day);
+    for (part, expected) in answers {
+        let answer = runner::run(day, part, &input)
+            .unwrap_or_else(|e| panic!("day {} part {} failed: {}", day, part, e));
+        assert_eq!(answer, expected, "day {} part {}", day, part);
+    }
+}
+
+macro_rules! answer_tests {
+    ($($name:ident: $day:literal),* $(,)?) => {
+        $(
+            #[test]
+            fn $name() {
+                check_day($day);
+            }
+        )*
+    };
+}
+
+answer_tests!(
+    day01: 1,
+    day02: 2,
+    day03: 3,
+    day04: 4,
+    day05: 5,
+    day06: 6,
+    day07: 7,
+    day08: 8,
+    day09: 9,
+    day10: 10,
+    day11: 11,
+);
+
+#[test]
+fn every_solution_has_an_answer() {
+    for (day, part) in runner::solutions() {
+        assert!(
+            known_answers(day).iter().any(|(p, _)| *p == part),
+            "no recorded answer for day {} part {}",
+            day,
+            part
+        );
+    }
+}
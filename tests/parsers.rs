@@ -0,0 +1,99 @@
+//! Feeds malformed input to every parser, which must report an error instead of panicking.
+//! Crashing inputs found by the fuzz targets are kept in `fuzz/regressions/parse_dayNN`.
+
+use std::fs;
+use std::path::PathBuf;
+
+use proptest::prelude::*;
+
+use advent_of_code_2023::solution::{find, registry};
+
+fn root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+/// Characters of the puzzle inputs, plus some that never occur in them
+const ALPHABET: &str = "0123456789 \n-:;,|=().#*$SLJF7AKQTZRGN\té";
+
+/// Example inputs of a day, to mutate into almost valid input
+fn examples(day: u32) -> Vec<String> {
+    let dir = root().join(format!("examples/2023/day{}", day));
+    let mut examples: Vec<String> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|e| e == "txt"))
+        .map(|path| fs::read_to_string(path).unwrap())
+        .collect();
+    examples.sort();
+    examples
+}
+
+#[derive(Debug, Clone)]
+enum Edit {
+    Insert(usize, char),
+    Replace(usize, char),
+    Delete(usize),
+}
+
+fn edit() -> impl Strategy<Value = Edit> {
+    let position = any::<usize>();
+    let character = prop::sample::select(ALPHABET.chars().collect::<Vec<_>>());
+    prop_oneof![
+        (position, character.clone()).prop_map(|(p, c)| Edit::Insert(p, c)),
+        (position, character).prop_map(|(p, c)| Edit::Replace(p, c)),
+        position.prop_map(Edit::Delete),
+    ]
+}
+
+fn apply(input: &str, edits: &[Edit]) -> String {
+    let mut chars: Vec<char> = input.chars().collect();
+    for edit in edits {
+        match *edit {
+            Edit::Insert(p, c) => chars.insert(p % (chars.len() + 1), c),
+            Edit::Replace(p, c) if !chars.is_empty() => {
+                let len = chars.len();
+                chars[p % len] = c;
+            }
+            Edit::Delete(p) if !chars.is_empty() => {
+                let len = chars.len();
+                chars.remove(p % len);
+            }
+            _ => (),
+        }
+    }
+    chars.into_iter().collect()
+}
+
+#[test]
+fn test_regressions() {
+    for entry in registry() {
+        let dir = root().join(format!("fuzz/regressions/parse_day{:02}", entry.day));
+        let Ok(files) = fs::read_dir(dir) else {
+            continue;
+        };
+        for file in files {
+            let path = file.unwrap().path();
+            let data = fs::read(&path).unwrap();
+            // Day 1 reports malformed lines when solving a part, a panic fails the test either way
+            let _ = entry.parse(&String::from_utf8_lossy(&data));
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn test_random_input(day in 1..=11u32, input in "[0-9 \n:;,|=().#SLJF7AZ-]{0,60}") {
+        let _ = find(2023, day).unwrap().parse(&input);
+    }
+
+    #[test]
+    fn test_mutated_examples(
+        day in 1..=11u32,
+        example in any::<prop::sample::Index>(),
+        edits in prop::collection::vec(edit(), 1..5),
+    ) {
+        let examples = examples(day);
+        let input = apply(example.get::<String>(&examples), &edits);
+        let _ = find(2023, day).unwrap().parse(&input);
+    }
+}